@@ -0,0 +1,114 @@
+use super::{read_file_value, ConfigError};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+
+const EXTENDS_KEY: &str = "extends";
+const INCLUDES_KEY: &str = "includes";
+
+/// Reads a config and resolves `extends` and `includes`, with paths relative to the file
+/// they appear in. The result does not contain either key, and every other path in it,
+/// e.g. `catalog` or `formatter_config.templates`, is relative to the file it came from too.
+pub fn resolve(path: &Path) -> Result<Value, ConfigError> {
+    resolve_visiting(path, &mut Vec::new())
+}
+
+fn resolve_visiting(path: &Path, visiting: &mut Vec<PathBuf>) -> Result<Value, ConfigError> {
+    let canonical = path
+        .canonicalize()
+        .map_err(|_| ConfigError::FileSystemError)?;
+    if visiting.contains(&canonical) {
+        return Err(ConfigError::CyclicInheritance(path.to_path_buf()));
+    }
+    visiting.push(canonical);
+
+    let mut value = read_file_value(path)?;
+    let dir = path.parent().unwrap_or(Path::new(""));
+    resolve_paths(&mut value, dir);
+
+    if let Value::Object(object) = &mut value {
+        if let Some(base) = object.remove(EXTENDS_KEY) {
+            let base_path = dir.join(expect_path(&base, EXTENDS_KEY)?);
+            let base = resolve_visiting(&base_path, visiting)?;
+            value = merge(base, value);
+        }
+    }
+
+    if let Value::Object(object) = &mut value {
+        if let Some(includes) = object.remove(INCLUDES_KEY) {
+            let Value::Array(includes) = includes else {
+                return Err(ConfigError::InvalidInheritance(INCLUDES_KEY));
+            };
+
+            let mut work_infos = Vec::new();
+            for include in &includes {
+                let include_path = dir.join(expect_path(include, INCLUDES_KEY)?);
+                if let Some(Value::Array(included)) =
+                    resolve_visiting(&include_path, visiting)?.get_mut("work_infos")
+                {
+                    work_infos.append(included);
+                }
+            }
+
+            // Own works come after the included ones
+            if let Some(Value::Array(own)) = object.get_mut("work_infos") {
+                work_infos.append(own);
+            }
+            object.insert(String::from("work_infos"), Value::Array(work_infos));
+        }
+    }
+
+    visiting.pop();
+    Ok(value)
+}
+
+// Paths given in a config, other than those of other configs
+fn resolve_paths(value: &mut Value, dir: &Path) {
+    let Value::Object(object) = value else {
+        return;
+    };
+
+    if let Some(catalog) = object.get_mut("catalog") {
+        join_path(catalog, dir);
+    }
+    // Only the `Canonical` source takes a path instead of an address
+    if let Some(url) = object
+        .get_mut("source_url")
+        .filter(|url| url.as_str().is_some_and(|url| !url.contains("://")))
+    {
+        join_path(url, dir);
+    }
+    if let Some(templates) = object
+        .get_mut("formatter_config")
+        .and_then(|formatter_config| formatter_config.get_mut("templates"))
+    {
+        join_path(templates, dir);
+    }
+}
+
+fn join_path(path: &mut Value, dir: &Path) {
+    if let Value::String(path) = path {
+        *path = dir.join(&*path).to_string_lossy().into_owned();
+    }
+}
+
+fn expect_path<'a>(value: &'a Value, key: &'static str) -> Result<&'a str, ConfigError> {
+    value.as_str().ok_or(ConfigError::InvalidInheritance(key))
+}
+
+/// Values of `overrides` replace the ones of `base`, objects are merged key by key.
+fn merge(base: Value, overrides: Value) -> Value {
+    match (base, overrides) {
+        (Value::Object(mut base), Value::Object(overrides)) => {
+            for (key, value) in overrides {
+                let merged = match base.remove(&key) {
+                    Some(base_value) => merge(base_value, value),
+                    None => value,
+                };
+                base.insert(key, merged);
+            }
+
+            Value::Object(base)
+        }
+        (_, overrides) => overrides,
+    }
+}
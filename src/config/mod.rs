@@ -0,0 +1,400 @@
+use crate::escape::escape_latex;
+use crate::formatters::{
+    latex, Counter, Hyphenation, Language, Numerals, SpeakerPlacement, TextFormatter, Work,
+};
+use crate::numerals::NumeralStyle;
+use crate::punctuation::{Punctuation, Rules};
+use crate::series::VolumeBudget;
+use crate::text_sources::cts::{Catalog, CtsApi};
+use crate::text_sources::{cache::Cache, GetTextError, TextSource};
+use crate::urn::CtsUrn;
+use crate::vocabulary::{Vocabulary, Word};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+use validation::Diagnostic;
+
+pub mod inheritance;
+pub mod validation;
+
+use crate::text_sources::{canonical, dts, scaife};
+
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema, clap::ValueEnum,
+)]
+pub enum TextSourceType {
+    Scaife,
+    Dts,
+    Canonical,
+}
+
+impl TextSourceType {
+    /// `url` replaces the default address of the library or API.
+    pub fn get_source(&self, cache: Option<Cache>, url: Option<&str>) -> Box<dyn TextSource> {
+        match self {
+            Self::Scaife => Box::new(
+                url.map(scaife::Scaife::with_library_url)
+                    .unwrap_or_default()
+                    .with_cache(cache),
+            ),
+            Self::Dts => Box::new(
+                url.map(dts::Dts::with_entry_url)
+                    .unwrap_or_default()
+                    .with_cache(cache),
+            ),
+            // Local files are not worth caching
+            Self::Canonical => Box::new(canonical::Canonical::new(url.unwrap_or("."))),
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::Scaife => "CTS URNs from scaife.perseus.org, or TEI files given as `file:<path>`",
+            Self::Dts => {
+                "Any DTS API given by `source_url`, with CTS URNs or `dts:<id>` identifiers"
+            }
+            Self::Canonical => {
+                "Local clone of canonical-greekLit or canonical-latinLit at `source_url`, with CTS URNs"
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, JsonSchema, clap::ValueEnum)]
+pub enum TextFormatterType {
+    Latex,
+}
+
+impl TextFormatterType {
+    pub fn get_formatter(&self) -> Box<dyn TextFormatter> {
+        Box::new(match self {
+            Self::Latex => latex::Latex::new(),
+        })
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::Latex => "LaTeX document typeset in the style of early modern editions",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConfigFormat {
+    Json,
+    Toml,
+    Yaml,
+}
+
+impl ConfigFormat {
+    /// Picks the format from the file extension, JSON is used for unknown extensions.
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("toml") => Self::Toml,
+            Some("yaml" | "yml") => Self::Yaml,
+            _ => Self::Json,
+        }
+    }
+}
+
+// Every format is read into a JSON value first, so that validation and inheritance are shared
+fn read_file_value(path: &Path) -> Result<serde_json::Value, ConfigError> {
+    let text = std::fs::read_to_string(path).map_err(|_| ConfigError::FileSystemError)?;
+
+    Ok(match ConfigFormat::from_path(path) {
+        ConfigFormat::Json => serde_json::from_str(&text)?,
+        ConfigFormat::Toml => toml::from_str(&text)?,
+        ConfigFormat::Yaml => serde_yaml::from_str(&text)?,
+    })
+}
+
+#[derive(Error, Debug)]
+pub enum ConfigError {
+    #[error("the config file could not be opened")]
+    FileSystemError,
+    #[error("the config file could not be parsed: {0}")]
+    ParseError(#[from] serde_json::Error),
+    #[error("the config file could not be parsed: {0}")]
+    TomlParseError(#[from] toml::de::Error),
+    #[error("the config file could not be parsed: {0}")]
+    YamlParseError(#[from] serde_yaml::Error),
+    #[error("cyclic `extends` or `includes` reaching {0}")]
+    CyclicInheritance(PathBuf),
+    #[error("`{0}` has to contain paths of config files")]
+    InvalidInheritance(&'static str),
+    #[error("the config is invalid:\n{}", .0.iter().map(|d| d.to_string()).collect::<Vec<_>>().join("\n"))]
+    Invalid(Vec<Diagnostic>),
+}
+
+#[derive(Default, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct FormatterConfig {
+    /// Title of the whole book, printed on the title page
+    pub title: Option<String>,
+    pub author: Option<String>,
+    /// Print the first word of the next page at the bottom of each page
+    pub catchwords: bool,
+    /// Print reference numbers of the source (sections, lines) in the margin
+    pub ref_numbers: bool,
+    pub footnotes: bool,
+    pub language: Language,
+    /// Print the number of every n-th verse line, 5 if not given
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub line_number_interval: Option<usize>,
+    #[serde(default)]
+    pub speaker_placement: SpeakerPlacement,
+    /// Print an ornament between consecutive epigrams
+    #[serde(default)]
+    pub epigram_ornaments: bool,
+    /// Numeral style of books, chapters, epigrams, letters and page numbers
+    #[serde(default)]
+    pub numerals: Numerals,
+    /// Headings and other words printed around the texts, in place of the defaults of `language`
+    #[serde(default)]
+    pub vocabulary: Vocabulary,
+    /// Spacing, dashes, quotation marks and elision of each language, in place of the defaults
+    #[serde(default)]
+    pub punctuation: Punctuation,
+    /// Hyphenation patterns of each language and words to be broken differently
+    #[serde(default)]
+    pub hyphenation: Hyphenation,
+    /// Directory with templates replacing the built-in ones, e.g. `preamble.tex`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub templates: Option<PathBuf>,
+}
+
+impl FormatterConfig {
+    pub const LINE_NUMBER_INTERVAL: usize = 5;
+
+    /// Whether the number of a line is printed, e.g. `10` but not `12` or `12a` every 5 lines.
+    pub fn prints_line_number(&self, number: &str) -> bool {
+        let interval = self
+            .line_number_interval
+            .unwrap_or(Self::LINE_NUMBER_INTERVAL);
+
+        interval > 0
+            && number
+                .parse::<usize>()
+                .is_ok_and(|number| number % interval == 0)
+    }
+
+    pub fn numeral_style(&self, counter: Counter) -> NumeralStyle {
+        self.numerals.style(counter, &self.language)
+    }
+
+    pub fn word(&self, word: Word) -> &str {
+        self.vocabulary.word(word, &self.language)
+    }
+
+    /// Word escaped for LaTeX, since words given in the config may contain `&` or `%`.
+    pub fn latex_word(&self, word: Word) -> String {
+        escape_latex(self.word(word))
+    }
+
+    pub fn punctuation_rules(&self) -> Rules {
+        self.punctuation.rules(&self.language)
+    }
+
+    pub fn apply(self, formatter: &mut dyn TextFormatter) {
+        formatter.set_title(self.title);
+        formatter.set_author(self.author);
+        formatter.set_catchwords(self.catchwords);
+        formatter.set_margin_notes(self.ref_numbers);
+        formatter.set_footnotes(self.footnotes);
+        formatter.set_language(self.language);
+        formatter.set_line_number_interval(self.line_number_interval);
+        formatter.set_speaker_placement(self.speaker_placement);
+        formatter.set_epigram_ornaments(self.epigram_ornaments);
+        formatter.set_numerals(self.numerals);
+        formatter.set_vocabulary(self.vocabulary);
+        formatter.set_punctuation(self.punctuation);
+        formatter.set_hyphenation(self.hyphenation);
+        formatter.set_templates(self.templates);
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+pub struct WorkInfo {
+    /// Taken from the TEI header of the text if not given
+    pub title: Option<String>,
+    // It's popular to have bilingual work names in Greek books
+    /// Second title, printed below the main one and in the table of contents
+    pub alt_title: Option<String>,
+    /// Taken from the TEI header of the text if not given
+    pub author: Option<String>,
+    /// Identifier of the text in the source, e.g. a CTS URN or `file:<path>`
+    pub identifier: String,
+}
+
+impl WorkInfo {
+    pub fn into_work(self, source: &dyn TextSource) -> Work {
+        let (text, metadata) = source.get_text_with_metadata(&self.identifier).unwrap();
+        let metadata = metadata.unwrap_or_default();
+
+        Work {
+            // The identifier is the only name left when neither the config nor the source has one
+            title: self
+                .title
+                .or_else(|| metadata.title.clone())
+                .unwrap_or(self.identifier),
+            alt_title: self.alt_title,
+            author: self.author.or_else(|| metadata.author.clone()),
+            metadata,
+            text,
+        }
+    }
+}
+
+/// Works picked from the catalog of the library instead of being listed one by one.
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+pub struct WorkSelection {
+    /// URN of a text group, e.g. `urn:cts:greekLit:tlg0062`, or of a single work
+    pub urn: String,
+    /// Beginning of the version part of the edition URN, e.g. `perseus-grc`;
+    /// the first edition which is not a translation is used if not given
+    pub edition: Option<String>,
+}
+
+// Edition URNs such as `urn:cts:greekLit:tlg0012.tlg001.perseus-grc2`, without a passage
+fn is_whole_edition(identifier: &str) -> bool {
+    identifier
+        .parse::<CtsUrn>()
+        .is_ok_and(|urn| urn.is_edition() && urn.passage.is_none())
+}
+
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+pub struct Config {
+    /// Config providing every value missing from this one, objects are merged key by key
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    extends: Option<PathBuf>,
+    /// Configs whose works are listed before the works of this one
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    includes: Vec<PathBuf>,
+    name: String,
+    formatter_type: TextFormatterType,
+    formatter_config: FormatterConfig,
+    source_type: TextSourceType,
+    /// Address of the library or API, e.g. the entry point of a DTS API,
+    /// or the path of the repository for the `Canonical` source
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    source_url: Option<String>,
+    #[serde(default)]
+    work_infos: Vec<WorkInfo>,
+    /// Whole text groups or works, added after `work_infos`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    selections: Vec<WorkSelection>,
+    /// CTS `GetCapabilities` reply to use instead of asking the library for its catalog
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    catalog: Option<PathBuf>,
+    /// Split the works into a series of volumes of at most this size
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    volume_budget: Option<VolumeBudget>,
+}
+
+impl Config {
+    pub fn from_path(path: &Path) -> Result<Self, ConfigError> {
+        let value = Self::read_value(path)?;
+        let errors: Vec<_> = validation::validate(&value)
+            .into_iter()
+            .filter(Diagnostic::is_error)
+            .collect();
+
+        if !errors.is_empty() {
+            return Err(ConfigError::Invalid(errors));
+        }
+
+        Ok(serde_json::from_value(value)?)
+    }
+
+    /// All errors and warnings about a config, found without accessing the text source.
+    pub fn diagnostics(path: &Path) -> Result<Vec<Diagnostic>, ConfigError> {
+        Ok(validation::validate(&Self::read_value(path)?))
+    }
+
+    fn read_value(path: &Path) -> Result<serde_json::Value, ConfigError> {
+        inheritance::resolve(path)
+    }
+
+    /// JSON Schema of the config, for autocompletion and validation in editors.
+    pub fn json_schema() -> String {
+        serde_json::to_string_pretty(&schemars::schema_for!(Config)).unwrap()
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn source(&self, cache: Option<Cache>) -> Box<dyn TextSource> {
+        self.source_type
+            .get_source(cache, self.source_url.as_deref())
+    }
+
+    pub fn work_infos(&self) -> &[WorkInfo] {
+        &self.work_infos
+    }
+
+    /// Works listed in the config followed by the selected ones, with edition URNs
+    /// without a passage extended to the whole edition.
+    ///
+    /// The catalog of the library is only read if there is something to look up.
+    pub fn resolve_work_infos(&self, cts: &CtsApi) -> Result<Vec<WorkInfo>, GetTextError> {
+        // DTS and local repositories serve whole documents by themselves
+        let is_whole_edition = |identifier: &str| {
+            self.source_type == TextSourceType::Scaife && is_whole_edition(identifier)
+        };
+        let needs_catalog = !self.selections.is_empty()
+            || self
+                .work_infos
+                .iter()
+                .any(|work_info| is_whole_edition(&work_info.identifier));
+        if !needs_catalog {
+            return Ok(self.work_infos.clone());
+        }
+
+        let catalog = match &self.catalog {
+            Some(path) => Catalog::from_path(path)?,
+            None => cts.capabilities()?,
+        };
+
+        let mut work_infos = Vec::new();
+        for work_info in &self.work_infos {
+            let mut work_info = work_info.clone();
+            if is_whole_edition(&work_info.identifier) {
+                let edition = catalog
+                    .edition(&work_info.identifier)
+                    .ok_or_else(|| GetTextError::NotInCatalog(work_info.identifier.clone()))?;
+                work_info.identifier = cts.full_range(edition)?.to_string();
+            }
+
+            work_infos.push(work_info);
+        }
+
+        for selection in &self.selections {
+            for (work, edition) in catalog.select(&selection.urn, selection.edition.as_deref())? {
+                work_infos.push(WorkInfo {
+                    title: work.title.clone(),
+                    alt_title: None,
+                    author: None,
+                    identifier: cts.full_range(edition)?.to_string(),
+                });
+            }
+        }
+
+        Ok(work_infos)
+    }
+
+    pub fn volume_budget(&self) -> Option<VolumeBudget> {
+        self.volume_budget
+    }
+
+    pub fn formatter(&self) -> Box<dyn TextFormatter> {
+        let mut formatter = self.formatter_type.get_formatter();
+        self.formatter_config.clone().apply(formatter.as_mut());
+
+        formatter
+    }
+
+    pub fn take_work_infos(self) -> Vec<WorkInfo> {
+        self.work_infos
+    }
+}
@@ -0,0 +1,342 @@
+use super::{Config, FormatterConfig, TextSourceType, WorkInfo, WorkSelection};
+use crate::formatters::templates::Templates;
+use crate::formatters::{Hyphenation, Numerals};
+use crate::punctuation::{LanguageRules, Punctuation};
+use crate::urn::CtsUrn;
+use crate::vocabulary::Vocabulary;
+use serde::de::{self, Deserialize, Deserializer, Visitor};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::Path;
+
+// Editors find the JSON Schema of a file through it
+const SCHEMA_KEY: &str = "$schema";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// A single problem with a config, located by its JSON path, e.g. `$.work_infos[3].identifier`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub path: String,
+    pub message: String,
+}
+
+impl Diagnostic {
+    fn error(path: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Error,
+            path: path.into(),
+            message: message.into(),
+        }
+    }
+
+    fn warning(path: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Warning,
+            path: path.into(),
+            message: message.into(),
+        }
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+}
+
+impl std::fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let severity = match self.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+
+        write!(f, "{severity}: {}: {}", self.path, self.message)
+    }
+}
+
+/// Checks a config without accessing any text source.
+pub fn validate(value: &Value) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+
+    check_keys::<Config>(value, "$", &mut diagnostics);
+    if let Some(formatter_config) = value.get("formatter_config") {
+        check_keys::<FormatterConfig>(formatter_config, "$.formatter_config", &mut diagnostics);
+        if let Some(numerals) = formatter_config.get("numerals") {
+            check_keys::<Numerals>(numerals, "$.formatter_config.numerals", &mut diagnostics);
+        }
+        if let Some(vocabulary) = formatter_config.get("vocabulary") {
+            check_keys::<Vocabulary>(
+                vocabulary,
+                "$.formatter_config.vocabulary",
+                &mut diagnostics,
+            );
+        }
+        if let Some(hyphenation) = formatter_config.get("hyphenation") {
+            check_keys::<Hyphenation>(
+                hyphenation,
+                "$.formatter_config.hyphenation",
+                &mut diagnostics,
+            );
+        }
+        if let Some(punctuation) = formatter_config.get("punctuation") {
+            let path = "$.formatter_config.punctuation";
+            check_keys::<Punctuation>(punctuation, path, &mut diagnostics);
+            for language in ["latin", "greek"] {
+                if let Some(rules) = punctuation.get(language) {
+                    let path = format!("{path}.{language}");
+                    check_keys::<LanguageRules>(rules, &path, &mut diagnostics);
+                }
+            }
+        }
+    }
+    if let Some(Value::Array(work_infos)) = value.get("work_infos") {
+        for (i, work_info) in work_infos.iter().enumerate() {
+            check_keys::<WorkInfo>(work_info, &format!("$.work_infos[{i}]"), &mut diagnostics);
+        }
+    }
+    if let Some(Value::Array(selections)) = value.get("selections") {
+        for (i, selection) in selections.iter().enumerate() {
+            check_keys::<WorkSelection>(selection, &format!("$.selections[{i}]"), &mut diagnostics);
+        }
+    }
+
+    match serde_path_to_error::deserialize::<_, Config>(value) {
+        Ok(config) => {
+            check_work_infos(&config.work_infos, config.source_type, &mut diagnostics);
+            check_selections(&config, &mut diagnostics);
+            check_source(&config, &mut diagnostics);
+            check_templates(&config.formatter_config, &mut diagnostics);
+            check_hyphenation(&config.formatter_config.hyphenation, &mut diagnostics);
+        }
+        Err(e) => diagnostics.push(Diagnostic::error(
+            json_path(e.path()),
+            e.inner().to_string(),
+        )),
+    }
+
+    diagnostics
+}
+
+fn json_path(path: &serde_path_to_error::Path) -> String {
+    match path.to_string().as_str() {
+        "." => String::from("$"),
+        path => format!("$.{path}"),
+    }
+}
+
+fn check_keys<'de, T: Deserialize<'de>>(
+    value: &Value,
+    path: &str,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let Value::Object(object) = value else {
+        return;
+    };
+
+    let fields = struct_fields::<T>();
+    for key in object
+        .keys()
+        .filter(|key| !fields.contains(&key.as_str()) && *key != SCHEMA_KEY)
+    {
+        let message = match suggest(key, fields) {
+            Some(suggestion) => format!("unknown key `{key}`, did you mean `{suggestion}`?"),
+            None => format!("unknown key `{key}`"),
+        };
+
+        diagnostics.push(Diagnostic::error(format!("{path}.{key}"), message));
+    }
+}
+
+fn suggest<'a>(key: &str, candidates: &[&'a str]) -> Option<&'a str> {
+    // Same threshold as used by clap for its suggestions
+    candidates
+        .iter()
+        .map(|candidate| (strsim::jaro(key, candidate), *candidate))
+        .filter(|(confidence, _)| *confidence > 0.7)
+        .max_by(|(a, _), (b, _)| a.total_cmp(b))
+        .map(|(_, candidate)| candidate)
+}
+
+fn check_work_infos(
+    work_infos: &[WorkInfo],
+    source_type: TextSourceType,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let mut titles = HashMap::new();
+    for (i, work_info) in work_infos.iter().enumerate() {
+        let path = format!("$.work_infos[{i}]");
+
+        if let Some(title) = &work_info.title {
+            if let Some(first) = titles.insert(title, i) {
+                diagnostics.push(Diagnostic::warning(
+                    format!("{path}.title"),
+                    format!(
+                        "duplicate work title `{title}`, first used at $.work_infos[{first}].title"
+                    ),
+                ));
+            }
+        }
+
+        // Only TEI files are sure to have a header with the title
+        if work_info.title.is_none() && !work_info.identifier.starts_with("file:") {
+            diagnostics.push(Diagnostic::warning(
+                format!("{path}.title"),
+                format!(
+                    "no title is given for `{}`, which is printed instead unless the source has one",
+                    work_info.identifier
+                ),
+            ));
+        }
+
+        if let Some(message) = check_identifier(&work_info.identifier, source_type) {
+            diagnostics.push(Diagnostic::error(format!("{path}.identifier"), message));
+        }
+    }
+}
+
+fn check_selections(config: &Config, diagnostics: &mut Vec<Diagnostic>) {
+    if config.work_infos.is_empty() && config.selections.is_empty() {
+        diagnostics.push(Diagnostic::error("$.work_infos", "no works are listed"));
+    }
+
+    for (i, selection) in config.selections.iter().enumerate() {
+        let urn = selection.urn.parse::<CtsUrn>();
+        if !urn.is_ok_and(|urn| !urn.is_edition() && urn.passage.is_none()) {
+            diagnostics.push(Diagnostic::error(
+                format!("$.selections[{i}].urn"),
+                format!(
+                    "`{}` is not the CTS URN of a text group or a work",
+                    selection.urn
+                ),
+            ));
+        }
+    }
+
+    if let Some(catalog) = config.catalog.as_ref().filter(|path| !path.is_file()) {
+        diagnostics.push(Diagnostic::error(
+            "$.catalog",
+            format!("file `{}` does not exist", catalog.display()),
+        ));
+    }
+}
+
+fn check_source(config: &Config, diagnostics: &mut Vec<Diagnostic>) {
+    if config.source_type != TextSourceType::Canonical {
+        return;
+    }
+
+    match &config.source_url {
+        None => diagnostics.push(Diagnostic::error(
+            "$.source_url",
+            "the `Canonical` source needs the path of a local repository",
+        )),
+        Some(path) if !Path::new(path).is_dir() => diagnostics.push(Diagnostic::error(
+            "$.source_url",
+            format!("directory `{path}` does not exist"),
+        )),
+        Some(_) => {}
+    }
+}
+
+fn check_templates(formatter_config: &FormatterConfig, diagnostics: &mut Vec<Diagnostic>) {
+    let Some(dir) = &formatter_config.templates else {
+        return;
+    };
+
+    if !dir.is_dir() {
+        diagnostics.push(Diagnostic::error(
+            "$.formatter_config.templates",
+            format!("directory `{}` does not exist", dir.display()),
+        ));
+    } else if let Err(e) = Templates::load(Some(dir)) {
+        diagnostics.push(Diagnostic::error(
+            "$.formatter_config.templates",
+            e.to_string(),
+        ));
+    }
+}
+
+fn check_hyphenation(hyphenation: &Hyphenation, diagnostics: &mut Vec<Diagnostic>) {
+    for (i, word) in hyphenation.exceptions.iter().enumerate() {
+        // Written as they are into `\babelhyphenation`
+        let is_word = !word.is_empty()
+            && !word
+                .chars()
+                .any(|c| c.is_whitespace() || r"\{}%#$&^_~".contains(c));
+        if !is_word {
+            diagnostics.push(Diagnostic::error(
+                format!("$.formatter_config.hyphenation.exceptions[{i}]"),
+                format!("`{word}` is not a single word with hyphens at its breaks"),
+            ));
+        }
+    }
+}
+
+fn check_identifier(id: &str, source_type: TextSourceType) -> Option<String> {
+    if let Some(resource) = id.strip_prefix("dts:") {
+        if source_type != TextSourceType::Dts {
+            Some(format!("`{id}` can only be used with the `Dts` source"))
+        } else {
+            resource
+                .is_empty()
+                .then(|| String::from("the DTS identifier is empty"))
+        }
+    } else if let Some(path) = id.strip_prefix("file:") {
+        (!Path::new(path).is_file()).then(|| format!("file `{path}` does not exist"))
+    } else if id.starts_with("urn:") {
+        match id.parse::<CtsUrn>() {
+            Err(e) => Some(format!("`{id}` is not a well formed CTS URN: {e}")),
+            Ok(urn)
+                if urn
+                    .passage
+                    .as_ref()
+                    .is_some_and(|passage| !passage.is_ordered()) =>
+            {
+                Some(format!("the passage of `{id}` ends before it starts"))
+            }
+            Ok(_) => None,
+        }
+    } else {
+        Some(format!(
+            "unknown identifier prefix in `{id}`, expected `urn:`, `file:` or `dts:`"
+        ))
+    }
+}
+
+/// Names of the fields of a struct deserialized by `serde_derive`.
+fn struct_fields<'de, T: Deserialize<'de>>() -> &'static [&'static str] {
+    let mut fields = &[][..];
+    let _ = T::deserialize(FieldNames(&mut fields));
+    fields
+}
+
+struct FieldNames<'a>(&'a mut &'static [&'static str]);
+
+impl<'de> Deserializer<'de> for FieldNames<'_> {
+    type Error = de::value::Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Self::Error> {
+        Err(de::Error::custom("only structs have field names"))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        _visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        *self.0 = fields;
+        Err(de::Error::custom("field names collected"))
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map enum identifier ignored_any
+    }
+}
@@ -0,0 +1,69 @@
+/// Characters without a glyph in the fonts of the books, with what is printed instead.
+pub const GLYPH_FALLBACKS: [(char, &str); 11] = [
+    // Roman numerals of thousands, as in `CIↃ`
+    ('ↄ', r"\rotatebox[origin=c]{180}{c}"),
+    ('Ↄ', r"\rotatebox[origin=c]{180}{C}"),
+    // Stigma, e.g. 6 in Greek numerals, written as the letters it stands for
+    ('ϛ', "στ"),
+    ('Ϛ', "ΣΤ"),
+    // Epigraphic letters of Latin inscriptions
+    ('ꟻ', r"\reflectbox{F}"),
+    ('ꟼ', r"\reflectbox{P}"),
+    ('ꟽ', r"\rotatebox[origin=c]{180}{M}"),
+    ('ꟾ', "I"),
+    ('ꟿ', "M"),
+    // Half brackets around letters which are only partly legible
+    ('⸢', r"\ensuremath{\ulcorner}"),
+    ('⸣', r"\ensuremath{\urcorner}"),
+];
+
+// Sources sometimes escape XML twice, leaving entities in the text
+const ENTITIES: [(&str, char); 5] = [
+    ("&amp;", '&'),
+    ("&lt;", '<'),
+    ("&gt;", '>'),
+    ("&quot;", '"'),
+    ("&apos;", '\''),
+];
+
+/// Text as LaTeX source printing it literally, in a single pass.
+/// Must be applied to each piece of text exactly once, before any markup is added.
+pub fn escape_latex(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        let entity = ENTITIES
+            .iter()
+            .find(|(entity, _)| c == '&' && rest.starts_with(entity));
+        match entity {
+            Some((entity, decoded)) => {
+                push_escaped(&mut escaped, *decoded);
+                rest = &rest[entity.len()..];
+            }
+            None => {
+                push_escaped(&mut escaped, c);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+    }
+
+    escaped
+}
+
+fn push_escaped(text: &mut String, c: char) {
+    match c {
+        '#' | '$' | '%' | '&' | '_' | '{' | '}' => {
+            text.push('\\');
+            text.push(c);
+        }
+        '\\' => text.push_str(r"\textbackslash{}"),
+        '^' => text.push_str(r"\textasciicircum{}"),
+        '~' => text.push_str(r"\textasciitilde{}"),
+        '<' => text.push_str(r"\textless{}"),
+        '>' => text.push_str(r"\textgreater{}"),
+        c => match GLYPH_FALLBACKS.iter().find(|(glyph, _)| *glyph == c) {
+            Some((_, fallback)) => text.push_str(fallback),
+            None => text.push(c),
+        },
+    }
+}
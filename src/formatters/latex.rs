@@ -1,11 +1,30 @@
-use super::{Language, TextFormatter, Work};
+use super::templates::{TemplateError, Templates};
+use super::{
+    Counter, Hyphenation, Language, Numerals, SpeakerPlacement, TextFormatter, Volume, Work,
+};
 use crate::config::FormatterConfig;
+use crate::numerals::{self, NumeralStyle};
+use crate::punctuation::{self, Punctuation};
 use crate::text::*;
-use regex::Regex;
+use crate::vocabulary::{Vocabulary, Word};
+use minijinja::context;
+use std::path::PathBuf;
+
+// Comments marking where each work starts in the generated document,
+// so that errors reported by LaTeX can be traced back to works
+pub const WORK_MARKER: &str = "% egrapsa:work ";
+pub const BACK_MATTER_MARKER: &str = "% egrapsa:back-matter";
 
 pub struct Latex {
     config: FormatterConfig,
     works: Vec<Work>,
+    volume: Option<Volume>,
+}
+
+impl Default for Latex {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Latex {
@@ -13,31 +32,102 @@ impl Latex {
         Self {
             config: FormatterConfig::default(),
             works: Vec::default(),
+            volume: None,
         }
     }
 
-    fn get_language_packages(&self) -> &str {
-        match self.config.language {
-            Language::Latin => {
-                r"
-\usepackage[latin]{babel}
-\usepackage[oldstyle, veryoldstyle]{kpfonts}"
-            }
-            Language::Greek => {
-                r"
-\usepackage[greek.polutoniko]{babel}
-\usepackage{TheanoOldStyle}"
+    fn volume_name(&self, number: usize) -> String {
+        format!(
+            "{} {}",
+            self.config.latex_word(Word::Volume),
+            numerals::roman(number)
+        )
+    }
+
+    // Falls back to the author of the works when they all share one
+    fn author(&self) -> Option<&str> {
+        self.config.author.as_deref().or_else(|| {
+            let mut authors = self.works.iter().map(|work| work.author.as_deref());
+            let first = authors.next()??;
+            authors.all(|author| author == Some(first)).then_some(first)
+        })
+    }
+
+    fn format_editors_note(&self) -> String {
+        let works: Vec<_> = self
+            .works
+            .iter()
+            .filter(|work| work.metadata.has_edition_notes())
+            .collect();
+        if works.is_empty() {
+            return String::new();
+        }
+
+        let heading = self.config.latex_word(Word::EditorsNote);
+        let mut text = format!(
+            r"
+\clearpage
+\renewcommand{{\versohead}}{{{heading}.}}
+\likechapter{{{heading}.}}
+"
+        );
+
+        for work in works {
+            let metadata = &work.metadata;
+            let mut notes = Vec::new();
+            notes.extend(metadata.edition.clone());
+            if !metadata.editors.is_empty() {
+                notes.push(format!(
+                    "{}: {}",
+                    self.config.word(Word::Editors),
+                    metadata.editors.join(", ")
+                ));
             }
+            notes.extend(
+                metadata
+                    .source
+                    .iter()
+                    .map(|source| format!("{}: {source}", self.config.word(Word::Source))),
+            );
+            notes.extend(
+                metadata.responsibilities.iter().map(|responsibility| {
+                    format!("{}: {}", responsibility.resp, responsibility.name)
+                }),
+            );
+            notes.extend(
+                metadata
+                    .licence
+                    .iter()
+                    .map(|licence| format!("{}: {licence}", self.config.word(Word::Licence))),
+            );
+
+            let notes: Vec<_> = notes
+                .into_iter()
+                .map(|note| {
+                    format!("{}.", note.trim_end_matches('.')).format_for_latex(&self.config)
+                })
+                .collect();
+            text.push_str(&format!(
+                "\\noindent\\textbf{{{}.}} {}\\par\\medskip\n",
+                work.title,
+                notes.join(" ")
+            ));
         }
+
+        text
     }
 
-    // Replace some characters not likely to be found in fonts
-    fn normalize(mut text: String) -> String {
-        text = text.replace("ↄ", r"\rotatebox[origin=c]{180}{c}"); // Roman numeral ↄ
-        let marginpar_regex = Regex::new(r" \\refnumber\{(.*)\} ").unwrap();
-        marginpar_regex
-            .replace_all(&text, "\\refnumber{$1}")
-            .to_string()
+    fn get_language_packages(&self) -> String {
+        let babel = self
+            .config
+            .hyphenation
+            .babel_language(&self.config.language);
+        let fonts = match self.config.language {
+            Language::Latin => r"\usepackage[oldstyle, veryoldstyle]{kpfonts}",
+            Language::Greek => r"\usepackage{TheanoOldStyle}",
+        };
+
+        format!("\n\\usepackage[{babel}]{{babel}}\n{fonts}")
     }
 }
 
@@ -66,6 +156,7 @@ impl TextFormatter for Latex {
         let work = Work {
             title: work.title.format_for_latex(&self.config),
             alt_title: work.alt_title.map(|x| x.format_for_latex(&self.config)),
+            author: work.author.map(|x| x.format_for_latex(&self.config)),
             ..work
         };
 
@@ -76,284 +167,153 @@ impl TextFormatter for Latex {
         self.config.language = language;
     }
 
-    fn format(&self) -> String {
-        let mut text = String::from(
-            r"
-\documentclass[a5paper,12pt]{book}
-
-\usepackage{csquotes, dirtytalk, marginnote, lipsum, scrextend, xcolor, graphicx, amssymb, amstext, amsmath, epstopdf, booktabs, verbatim, gensymb, geometry, appendix, natbib, lmodern}
-\usepackage[pagestyles]{titlesec}
-\usepackage{fancyhdr}
-\usepackage{needspace}
-\usepackage{etoolbox}
-\usepackage{mparhack}
-\usepackage{psvectorian}
-\geometry{a5paper, bottom=2.5cm}
-
-\usepackage[utf8]{inputenc}",
-        );
-        text.push_str(self.get_language_packages());
-        if self.config.catchwords {
-            text.push_str("\\usepackage{fwlw}");
-        }
-
-        text.push_str(
-            r"
-\usepackage{fontspec}
-\usepackage{tocloft}
-\usepackage[perpage]{footmisc}
-
-% Show paragraphs in ToC (actually not used for paragraphs but for chapters)
-\setcounter{tocdepth}{4}
-\setcounter{secnumdepth}{4}
-
-\usepackage{enumitem}
-\makeatletter
-\newcommand{\greekalpha}[1]{\c@greekalpha{#1}}
-\newcommand{\c@greekalpha}[1]{%
-  {%
-    \ifcase\number\value{#1}%
-    \or α´\or β´\or γ´\or δ´\or ε´\or ϛ´\or ζ´\or η´\or θ´\or ι´%
-    \or ια´\or ιβ´\or ιγ´\or ιδ´\or ιε´\or ιϛ´\or ιζ´\or ιη´\or ιθ´%
-    \or κα´\or κβ´\or κγ´\or κδ´\or κε´\or κϛ´\or κζ´\or κη´\or κθ´%
-    \or λα´\or λβ´\or λγ´\or λδ´\or λε´\or λϛ´\or λζ´\or λη´\or λθ´%
-    \or μα´\or μβ´\or μγ´\or μδ´\or με´\or μϛ´\or μζ´\or μη´\or μθ´%
-    \or να´\or νβ´\or νγ´\or νδ´\or νε´\or νϛ´\or νζ´\or νη´\or νθ´%
-    \fi
-  }%
-}
-
-\AddEnumerateCounter*{\greekalpha}{\c@greekalpha}{5}
-\makeatother
+    fn set_line_number_interval(&mut self, interval: Option<usize>) {
+        self.config.line_number_interval = interval;
+    }
 
-\usepackage{sectsty}
-\allsectionsfont{\centering}
+    fn set_speaker_placement(&mut self, placement: SpeakerPlacement) {
+        self.config.speaker_placement = placement;
+    }
 
-\newcommand{\refnumber}[1]{",
-        );
+    fn set_epigram_ornaments(&mut self, epigram_ornaments: bool) {
+        self.config.epigram_ornaments = epigram_ornaments;
+    }
 
-        if self.config.ref_numbers {
-            text.push_str(
-                r" {\scriptsize\color{gray}(#1)} ",
-            );
-        }
+    fn set_numerals(&mut self, numerals: Numerals) {
+        self.config.numerals = numerals;
+    }
 
-        text.push_str(r"}
-
-\date{}
-
-\makeatletter
-\renewcommand{\@seccntformat}[1]{%
-  \ifcsname prefix@#1\endcsname
-    \csname prefix@#1\endcsname
-  \else
-    \csname the#1\endcsname\quad
-  \fi}
-\newcommand\prefix@section{}
-\makeatother
-
-\titlespacing*{\chapter}{0pt}{0pt}{15pt}
-
-\newcommand{\likechapter}[1]{{\center\huge #1 \\
-\vspace{50pt}}}
-
-\titleformat{\chapter}[display]{\normalfont\bfseries}{}{0pt}{\Huge\center}
-\renewcommand{\chaptermark}[1]{\markboth{#1}{}}
-
-% Start new sections on new pages
-\AddToHook{cmd/section/before}{%
-    \ifnum\value{section}=1%
-    \else%
-        % If current page is odd, it means that that the page left to the new section is going to be empty,
-        % and so the title of the current work won't be visible anywhere. In that case it is added
-        % by \thispagestyle{sectionpage}. Otherwise we can use plain style.
-        \Ifthispageodd{%
-            \cleardoublepage\thispagestyle{sectionpage}%
-        }{%
-            \cleardoublepage\thispagestyle{plain}%
-        }%
-    \fi%
-}
+    fn set_vocabulary(&mut self, vocabulary: Vocabulary) {
+        self.config.vocabulary = vocabulary;
+    }
 
-\newcommand{\altchapter}{}
-\newcommand{\orgchapter}{}
-\newcommand{\orgsection}{}
-\newcommand{\rectohead}{}
-\newcommand{\versohead}{}
-\fancyhf{}
-\fancyhead[LE, RO]{\thepage}
-\fancyhead[CE]{\versohead}
-\fancyhead[CO]{\rectohead}
-\setlength{\headheight}{14.5pt}
-\setlength{\marginparpush}{-6pt}
-",
-        );
+    fn set_punctuation(&mut self, punctuation: Punctuation) {
+        self.config.punctuation = punctuation;
+    }
 
-        if self.config.catchwords {
-            text.push_str(
-                r"
-\fancyfoot[R]{\usebox\NextWordBox}
-",
-            );
-        }
+    fn set_hyphenation(&mut self, hyphenation: Hyphenation) {
+        self.config.hyphenation = hyphenation;
+    }
 
-        text.push_str(
-            r"
-\fancypagestyle{plain}{
-\fancyhf{}
-\fancyhead[RO, LE]{\thepage}
-",
-        );
+    fn set_templates(&mut self, templates: Option<PathBuf>) {
+        self.config.templates = templates;
+    }
 
-        if self.config.catchwords {
-            text.push_str(
-                r"
-\fancyfoot[R]{\usebox\NextWordBox}
-",
-            );
-        }
+    fn set_volume(&mut self, volume: Volume) {
+        let series_contents = volume
+            .series_contents
+            .into_iter()
+            .map(|titles| {
+                titles
+                    .into_iter()
+                    .map(|title| title.format_for_latex(&self.config))
+                    .collect()
+            })
+            .collect();
+
+        self.volume = Some(Volume {
+            series_contents,
+            ..volume
+        });
+    }
 
-        text.push_str(
+    fn format_spine_labels(&self, volume_count: usize) -> String {
+        let mut text = String::from(
             r"
-}
-\renewcommand\headrulewidth{0pt}
-\pagestyle{fancy}
-",
-        );
+\documentclass[12pt]{article}
 
-        // Use "versohead" on recto pages where new sections begin,
-        // so that name of the work is visible
-        text.push_str(
-            r"
-\fancypagestyle{sectionpage}{
-\fancyhf{}
-\fancyhead[CO]{\versohead}
-\fancyhead[RO, LE]{\thepage}
-",
+\usepackage{graphicx, geometry}
+\geometry{a4paper, landscape, margin=1cm}",
         );
-
-        if self.config.catchwords {
-            text.push_str(
-                r"
-\fancyfoot[R]{\usebox\NextWordBox}
-",
-            );
-        }
-        text.push_str("}");
-
-        if let Some(author) = self.config.author.as_ref() {
-            text.push_str(r"\author{");
-            text.push_str(&author);
-            text.push_str(r"}");
-        }
-
-        if let Some(title) = self.config.title.as_ref() {
-            text.push_str(r"\title{");
-            text.push_str(&title);
-            text.push_str(r"}");
-        }
-
+        text.push_str(&self.get_language_packages());
         text.push_str(
             r"
+\usepackage{fontspec}
 
 \begin{document}
+\pagestyle{empty}
+\noindent
 ",
         );
 
-        if self.config.title.is_some() {
-            text.push_str("\\maketitle\n");
-            text.push_str(r"\clearpage\null\thispagestyle{empty}");
+        let author = self.author().unwrap_or_default();
+        let title = self.config.title.as_deref().unwrap_or_default();
+        for number in 1..=volume_count {
+            text.push_str(&format!(
+                "\\fbox{{\\rotatebox{{90}}{{\\parbox{{17cm}}{{\\centering \\textsc{{{author}}}\\quad \\textbf{{{title}}}\\quad {}}}}}}}\\hspace{{5mm}}\n",
+                self.volume_name(number)
+            ));
         }
 
-        for (i, work) in self.works.iter().enumerate() {
-            if i != 0 {
-                text.push_str(
-                    r"
-\Ifthispageodd{%
-    \clearpage\null\thispagestyle{empty}
-}{%
-    \clearpage\null
-    \clearpage\null\thispagestyle{empty}
-}%
-",
-                );
-            }
-
-            text.push_str(&format!(r"\chapter*{{{}.}}", work.title));
-            if let Some(alt_title) = &work.alt_title {
-                text.push_str(&format!(
-                    r"
-\addtocontents{{toc}}{{\protect\vskip-10pt\needspace{{2\baselineskip}}}}
-\addtocontents{{toc}}{{\protect\contentsline{{chapter}}{{{}}}{{}}{{}}}}
-\addcontentsline{{toc}}{{paragraph}}{{\textbf{{({})}}}}
-",
-                    work.title, alt_title,
-                ));
-            } else {
-                text.push_str(&format!(
-                    r"
-\addcontentsline{{toc}}{{paragraph}}{{\textbf{{{}}}}}",
-                    work.title,
-                ));
-            }
+        text.push_str(r"\end{document}");
+        text
+    }
 
-            text.push_str("\\setcounter{section}{0}\n");
-            text.push_str("\\renewcommand{\\rectohead}{}\n");
-            text.push_str("\\renewcommand{\\orgsection}{}\n");
-            text.push_str("\\thispagestyle{plain}\n");
-            text.push_str(&format!(
-                "\\renewcommand{{\\orgchapter}}{{{}}}\n",
-                work.title
-            ));
-            text.push_str(&format!(
-                "\\renewcommand{{\\altchapter}}{{{}}}\n",
-                work.alt_title.as_ref().unwrap_or(&work.title)
-            ));
+    fn format(&self) -> Result<String, TemplateError> {
+        let templates = Templates::load(self.config.templates.as_deref())?;
+        let words = context! {
+            the_end => self.config.latex_word(Word::TheEnd),
+            contents => self.config.latex_word(Word::Contents),
+            series_contents => self.config.latex_word(Word::SeriesContents),
+            end_of_contents => self.config.latex_word(Word::EndOfContents),
+        };
+        let folios = self.config.numeral_style(Counter::Folio);
+        let works: Vec<_> = self
+            .works
+            .iter()
+            .map(|work| {
+                context! {
+                    title => work.title,
+                    alt_title => work.alt_title,
+                    author => work.author,
+                }
+            })
+            .collect();
+        let series_contents: Vec<_> = self
+            .volume
+            .iter()
+            .filter(|volume| volume.is_last())
+            .flat_map(|volume| volume.series_contents.iter().enumerate())
+            .map(|(i, titles)| {
+                context! {
+                    name => self.volume_name(i + 1),
+                    titles => titles,
+                }
+            })
+            .collect();
+        let context = context! {
+            options => self.config,
+            title => self.config.title,
+            author => self.author(),
+            babel => self.config.hyphenation.babel_language(&self.config.language),
+            volume => self.volume.as_ref().map(|volume| self.volume_name(volume.number)),
+            folios => (folios != NumeralStyle::Arabic).then(|| folios.latex_counter("page")),
+            words,
+            works,
+            series_contents,
+        };
 
-            if work.alt_title.is_some() {
-                text.push_str(
-                    r"
-\likechapter{\altchapter.}
-\renewcommand{\versohead}{\orgchapter.}
-",
-                );
-            } else {
-                text.push_str(
-                    r"
-\renewcommand{\versohead}{\orgchapter.}
-",
-                );
-            }
+        let rules = self.config.punctuation_rules();
+        let mut text = templates.render("preamble", &context)?;
+        text.push_str(&templates.render("title", &context)?);
+        text.push_str(&self.format_editors_note());
 
-            text.push_str(&work.text.format_for_latex(&self.config));
+        for (i, work) in self.works.iter().enumerate() {
+            text.push_str(&format!("\n{WORK_MARKER}{}\n", work.title));
+            text.push_str(&templates.render(
+                "work",
+                context! { first => i == 0, work => works[i], ..context.clone() },
+            )?);
+            text.push_str(&punctuation::normalize(
+                &work.text.format_for_latex(&self.config),
+                &rules,
+            ));
         }
 
-        text.push_str(
-            r"
-\vfill
-\center
-\begin{pspicture}(-1.5,-3.5)(1.5,1.5)%
-\rput(0,0){\Large \textbf{FINIS.}}
-\rput[t](0,-1.0){\psvectorian[width=5cm]{68}}
-\end{pspicture}%
-\renewcommand{\altchapter}{}
-\clearpage\null\thispagestyle{empty}
-\Ifthispageodd{%
-    \clearpage\null\thispagestyle{empty}
-    \clearpage\null\thispagestyle{empty}
-}{%
-    \clearpage\null\thispagestyle{empty}
-}%
-\renewcommand{\contentsname}{Index.}
-\setlength{\cftparaindent}{0pt}
-\renewcommand{\versohead}{Index.}
-\tableofcontents
-\vspace{1cm}
-\textbf{FINIS TABULÆ.}
-",
-        );
-        text.push_str(r"\end{document}");
+        text.push_str(&format!("\n{BACK_MATTER_MARKER}"));
+        text.push_str(&templates.render("end", &context)?);
+        text.push_str(&templates.render("index", &context)?);
 
-        Self::normalize(text)
+        Ok(text)
     }
 }
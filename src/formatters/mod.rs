@@ -1,22 +1,145 @@
+use crate::metadata::Metadata;
+use crate::numerals::NumeralStyle;
+use crate::punctuation::Punctuation;
 use crate::text::TextParent;
+use crate::vocabulary::Vocabulary;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use templates::TemplateError;
 
 pub struct Work {
     pub title: String,
     // It's popular to have bilingual work names in Greek books
     pub alt_title: Option<String>,
+    pub author: Option<String>,
+    /// Details of the edition, printed in the editor's note
+    pub metadata: Metadata,
     pub text: TextParent,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+/// Position of a book within a multi-volume series.
+pub struct Volume {
+    /// Counted from 1
+    pub number: usize,
+    /// Titles of the works of every volume in the series, used for the cumulative index
+    pub series_contents: Vec<Vec<String>>,
+}
+
+impl Volume {
+    pub fn is_last(&self) -> bool {
+        self.number == self.series_contents.len()
+    }
+}
+
+#[derive(Default, Clone, Serialize, Deserialize, JsonSchema)]
 pub enum Language {
+    #[default]
     Latin,
     Greek,
 }
 
-impl Default for Language {
-    fn default() -> Self {
-        Self::Latin
+/// Where the names of speakers in dialogues and plays are printed.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub enum SpeakerPlacement {
+    /// On a line of their own above the speech
+    #[default]
+    Centred,
+    Margin,
+}
+
+/// Counters whose numeral style can be chosen in the config.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Counter {
+    Book,
+    Chapter,
+    Epigram,
+    Letter,
+    /// Page numbers
+    Folio,
+}
+
+/// Numeral style of every counter, Roman numerals for Latin and Greek numerals for Greek
+/// books if not given, except for page numbers, which are Arabic.
+#[derive(Default, Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct Numerals {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub books: Option<NumeralStyle>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chapters: Option<NumeralStyle>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub epigrams: Option<NumeralStyle>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub letters: Option<NumeralStyle>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub folios: Option<NumeralStyle>,
+}
+
+impl Numerals {
+    pub fn style(&self, counter: Counter, language: &Language) -> NumeralStyle {
+        let style = match counter {
+            Counter::Book => self.books,
+            Counter::Chapter => self.chapters,
+            Counter::Epigram => self.epigrams,
+            Counter::Letter => self.letters,
+            Counter::Folio => return self.folios.unwrap_or(NumeralStyle::Arabic),
+        };
+
+        style.unwrap_or(match language {
+            Language::Latin => NumeralStyle::Roman,
+            Language::Greek => NumeralStyle::Greek,
+        })
+    }
+}
+
+/// Hyphenation patterns of Latin books.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub enum LatinHyphenation {
+    /// As in modern editions, e.g. `ma-gi-ster`
+    #[default]
+    Modern,
+    /// Following classical prosody, e.g. `ma-gis-ter`
+    Classical,
+    /// As in liturgical books, following ecclesiastical pronunciation
+    Liturgical,
+}
+
+/// Hyphenation patterns of Greek books.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub enum GreekHyphenation {
+    /// Patterns of modern polytonic Greek
+    #[default]
+    Modern,
+    Ancient,
+}
+
+/// How words are broken at the ends of lines.
+#[derive(Default, Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct Hyphenation {
+    #[serde(default)]
+    pub latin: LatinHyphenation,
+    #[serde(default)]
+    pub greek: GreekHyphenation,
+    /// Words broken differently from the patterns, with hyphens at all allowed breaks,
+    /// e.g. `Ἀ-χιλ-λεύς`, or none if they should never be broken
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exceptions: Vec<String>,
+}
+
+impl Hyphenation {
+    /// Language and its options loaded from babel, which come with the patterns.
+    pub fn babel_language(&self, language: &Language) -> &'static str {
+        match language {
+            Language::Latin => match self.latin {
+                LatinHyphenation::Modern => "latin",
+                LatinHyphenation::Classical => "classiclatin",
+                LatinHyphenation::Liturgical => "ecclesiasticlatin",
+            },
+            Language::Greek => match self.greek {
+                GreekHyphenation::Modern => "greek.polutoniko",
+                GreekHyphenation::Ancient => "greek.ancient",
+            },
+        }
     }
 }
 
@@ -27,8 +150,20 @@ pub trait TextFormatter {
     fn set_margin_notes(&mut self, margin_notes: bool);
     fn set_footnotes(&mut self, footnotes: bool);
     fn set_language(&mut self, language: Language);
+    fn set_line_number_interval(&mut self, interval: Option<usize>);
+    fn set_speaker_placement(&mut self, placement: SpeakerPlacement);
+    fn set_epigram_ornaments(&mut self, epigram_ornaments: bool);
+    fn set_numerals(&mut self, numerals: Numerals);
+    fn set_vocabulary(&mut self, vocabulary: Vocabulary);
+    fn set_punctuation(&mut self, punctuation: Punctuation);
+    fn set_hyphenation(&mut self, hyphenation: Hyphenation);
+    fn set_templates(&mut self, templates: Option<PathBuf>);
+    fn set_volume(&mut self, volume: Volume);
     fn add_work(&mut self, work: Work);
-    fn format(&self) -> String;
+    fn format(&self) -> Result<String, TemplateError>;
+    /// Separate document with labels for the spines of all volumes of a series
+    fn format_spine_labels(&self, volume_count: usize) -> String;
 }
 
 pub mod latex;
+pub mod templates;
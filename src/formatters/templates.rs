@@ -0,0 +1,72 @@
+use minijinja::syntax::SyntaxConfig;
+use minijinja::Environment;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+// Parts of a LaTeX book, each a template which can be replaced by a file of the same name,
+// e.g. `work.tex`, in the template directory of the config
+const BUILT_IN: [(&str, &str); 5] = [
+    ("preamble", include_str!("templates/preamble.tex")),
+    ("title", include_str!("templates/title.tex")),
+    ("work", include_str!("templates/work.tex")),
+    ("end", include_str!("templates/end.tex")),
+    ("index", include_str!("templates/index.tex")),
+];
+
+#[derive(Error, Debug)]
+pub enum TemplateError {
+    #[error("template `{0}` could not be read: {1}")]
+    ReadError(PathBuf, std::io::Error),
+    #[error("template `{0}` is invalid: {1}")]
+    SyntaxError(String, minijinja::Error),
+    #[error("template `{0}` could not be rendered: {1}")]
+    RenderError(String, minijinja::Error),
+}
+
+/// Templates of the parts of a LaTeX book.
+///
+/// Braces are everywhere in LaTeX, so statements are written as `\BLOCK{ if title }`,
+/// variables as `\VAR{ title }` and comments as `\#{ comment }`.
+pub struct Templates {
+    env: Environment<'static>,
+}
+
+impl Templates {
+    /// Built-in templates, with those found in `dir` instead of them.
+    pub fn load(dir: Option<&Path>) -> Result<Self, TemplateError> {
+        let mut env = Environment::new();
+        env.set_syntax(
+            SyntaxConfig::builder()
+                .block_delimiters(r"\BLOCK{", "}")
+                .variable_delimiters(r"\VAR{", "}")
+                .comment_delimiters(r"\#{", "}")
+                .build()
+                .expect("Template delimiters should be valid"),
+        );
+        env.set_trim_blocks(true);
+        env.set_lstrip_blocks(true);
+        env.set_keep_trailing_newline(true);
+
+        for (name, built_in) in BUILT_IN {
+            let source = match dir.map(|dir| dir.join(format!("{name}.tex"))) {
+                Some(path) if path.is_file() => {
+                    std::fs::read_to_string(&path).map_err(|e| TemplateError::ReadError(path, e))?
+                }
+                _ => built_in.to_string(),
+            };
+
+            env.add_template_owned(name, source)
+                .map_err(|e| TemplateError::SyntaxError(format!("{name}.tex"), e))?;
+        }
+
+        Ok(Self { env })
+    }
+
+    pub fn render(&self, name: &str, context: impl Serialize) -> Result<String, TemplateError> {
+        self.env
+            .get_template(name)
+            .and_then(|template| template.render(context))
+            .map_err(|e| TemplateError::RenderError(format!("{name}.tex"), e))
+    }
+}
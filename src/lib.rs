@@ -1,4 +1,12 @@
-pub mod text_sources;
+pub mod config;
+pub mod escape;
 pub mod formatters;
+pub mod metadata;
+pub mod numerals;
+pub mod pdf;
+pub mod punctuation;
+pub mod series;
 pub mod text;
-pub mod config;
+pub mod text_sources;
+pub mod urn;
+pub mod vocabulary;
@@ -1,33 +1,260 @@
-use clap::Parser;
-use egrapsa::config::Config;
-use std::path::PathBuf;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use egrapsa::config::WorkInfo;
+use egrapsa::config::{validation::Diagnostic, Config, TextFormatterType, TextSourceType};
+use egrapsa::formatters::{TextFormatter, Volume, Work};
+use egrapsa::pdf::{self, BadBox, BoxProblem, Engine};
+use egrapsa::series;
+use egrapsa::text_sources::{cache::Cache, cts::CtsApi};
+use std::path::{Path, PathBuf};
 
 use std::io::Write;
 
 #[derive(Parser)]
 #[command(version, about)]
 struct Cli {
+    /// Directory where downloaded sources are kept between runs
+    #[arg(long, global = true, default_value = Cache::DEFAULT_DIR)]
+    cache_dir: PathBuf,
+    /// Always download sources, without reading or filling the cache
+    #[arg(long, global = true)]
+    no_cache: bool,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Download all works of a config into the cache
+    Fetch {
+        #[command(flatten)]
+        config: ConfigArgs,
+        /// Download works again even if they are already cached
+        #[arg(long)]
+        refresh: bool,
+    },
+    /// Format all works of a config
+    Build {
+        #[command(flatten)]
+        config: ConfigArgs,
+        #[arg(short, long)]
+        output_path: PathBuf,
+        /// Compile the output to PDF with a locally installed LaTeX engine
+        #[arg(long)]
+        pdf: bool,
+        /// Engine used by --pdf, detected from PATH if not given
+        #[arg(long, requires = "pdf")]
+        engine: Option<Engine>,
+    },
+    /// Print the parsed text tree of a single work
+    Inspect {
+        identifier: String,
+        #[arg(short, long, default_value = "scaife")]
+        source: TextSourceType,
+        /// Address of the library or API, or path of a local repository,
+        /// instead of the default one of the source
+        #[arg(long)]
+        source_url: Option<String>,
+    },
+    /// Check a config without accessing the network
+    Validate {
+        #[command(flatten)]
+        config: ConfigArgs,
+    },
+    /// Write the JSON Schema of configs, for autocompletion in editors
+    Schema {
+        /// Print to standard output if not given
+        #[arg(short, long)]
+        output_path: Option<PathBuf>,
+    },
+    /// List available output formats
+    ListFormatters,
+    /// List available text sources
+    ListSources,
+}
+
+#[derive(Args)]
+struct ConfigArgs {
     #[arg(short, long)]
     config_path: PathBuf,
-    #[arg(short, long)]
-    output_path: PathBuf,
 }
 
-fn main() {
-    let cli = Cli::parse();
-    let config_file = std::fs::File::open(&cli.config_path).unwrap();
-    let config_reader = std::io::BufReader::new(config_file);
-    let config = serde_json::from_reader::<_, Config>(config_reader).unwrap();
+impl ConfigArgs {
+    fn load(&self) -> Config {
+        Config::from_path(&self.config_path).unwrap_or_else(|e| {
+            fail(format!("{}: {e}", self.config_path.display()));
+        })
+    }
+}
+
+fn fail(message: impl std::fmt::Display) -> ! {
+    eprintln!("{message}");
+    std::process::exit(1);
+}
+
+// Selections and whole editions are looked up in the catalog of the library
+fn work_infos(config: &Config, cache: &Option<Cache>) -> Vec<WorkInfo> {
+    config
+        .resolve_work_infos(&CtsApi::new().with_cache(cache.clone()))
+        .unwrap_or_else(|e| fail(format!("{}: {e}", config.name())))
+}
+
+fn fetch(config: Config, cache: Option<Cache>) {
+    let work_infos = work_infos(&config, &cache);
+    let source = config.source(cache);
+
+    for work_info in &work_infos {
+        println!("Fetching {}.", work_info.identifier);
+        if let Err(e) = source.fetch(&work_info.identifier) {
+            fail(format!("{}: {e}", work_info.identifier));
+        }
+    }
+}
 
+fn build(config: Config, cache: Option<Cache>, output_path: &Path, engine: Option<Engine>) {
     println!("Compiling {}.", config.name());
 
-    let mut formatter = config.formatter();
-    let source = config.source();
+    let work_infos = work_infos(&config, &cache);
+    let source = config.source(cache);
+    let works: Vec<_> = work_infos
+        .into_iter()
+        .map(|work_info| work_info.into_work(source.as_ref()))
+        .collect();
+
+    let Some(budget) = config.volume_budget() else {
+        write_book(config.formatter(), works, output_path, engine);
+        return;
+    };
+
+    let volumes = series::split_volumes(works, budget);
+    let series_contents = series::series_contents(&volumes);
+    let volume_count = volumes.len();
+    println!("Splitting into {volume_count} volumes.");
+
+    for (i, works) in volumes.into_iter().enumerate() {
+        let mut formatter = config.formatter();
+        formatter.set_volume(Volume {
+            number: i + 1,
+            series_contents: series_contents.clone(),
+        });
 
-    for work_info in config.take_work_infos() {
-        formatter.add_work(work_info.into_work(source.as_ref()));
+        let volume_path = series::volume_path(output_path, &(i + 1).to_string());
+        write_book(formatter, works, &volume_path, engine);
     }
 
-    let mut output_file = std::fs::File::create(&cli.output_path).unwrap();
-    write!(output_file, "{}", formatter.format()).unwrap();
+    let spines_path = series::volume_path(output_path, "spines");
+    std::fs::write(
+        &spines_path,
+        config.formatter().format_spine_labels(volume_count),
+    )
+    .unwrap_or_else(|e| fail(format!("{}: {e}", spines_path.display())));
+}
+
+fn write_book(
+    mut formatter: Box<dyn TextFormatter>,
+    works: Vec<Work>,
+    output_path: &Path,
+    engine: Option<Engine>,
+) {
+    for work in works {
+        formatter.add_work(work);
+    }
+
+    let text = formatter.format().unwrap_or_else(|e| fail(e));
+    let mut output_file = std::fs::File::create(output_path).unwrap();
+    write!(output_file, "{text}").unwrap();
+
+    if let Some(engine) = engine {
+        match pdf::compile(output_path, engine) {
+            Ok(compilation) => {
+                println!("Written {}.", compilation.pdf.display());
+                print_bad_boxes(&compilation.bad_boxes);
+            }
+            Err(e) => fail(e),
+        }
+    }
+}
+
+// Lines TeX could not break well, usually fixed with hyphenation exceptions
+fn print_bad_boxes(bad_boxes: &[BadBox]) {
+    if bad_boxes.is_empty() {
+        return;
+    }
+
+    let overfull = bad_boxes
+        .iter()
+        .filter(|bad_box| bad_box.problem == BoxProblem::Overfull)
+        .count();
+    println!(
+        "LaTeX reported {overfull} overfull and {} underfull boxes:",
+        bad_boxes.len() - overfull
+    );
+    for bad_box in bad_boxes {
+        println!("{bad_box}");
+    }
+}
+
+fn main() {
+    let cli = Cli::parse();
+    let cache = (!cli.no_cache).then(|| Cache::new(cli.cache_dir));
+
+    match cli.command {
+        Command::Fetch { config, refresh } => {
+            fetch(config.load(), cache.map(|cache| cache.refresh(refresh)))
+        }
+        Command::Build {
+            config,
+            output_path,
+            pdf,
+            engine,
+        } => {
+            let engine = pdf.then(|| {
+                engine
+                    .or_else(Engine::detect)
+                    .unwrap_or_else(|| fail(pdf::PdfError::NoEngine))
+            });
+            build(config.load(), cache, &output_path, engine)
+        }
+        Command::Inspect {
+            identifier,
+            source,
+            source_url,
+        } => {
+            match source
+                .get_source(cache, source_url.as_deref())
+                .get_text(&identifier)
+            {
+                Ok(text) => println!("{text:#?}"),
+                Err(e) => fail(format!("{identifier}: {e}")),
+            }
+        }
+        Command::Validate { config } => {
+            let path = config.config_path.display();
+            let diagnostics = Config::diagnostics(&config.config_path)
+                .unwrap_or_else(|e| fail(format!("{path}: {e}")));
+
+            for diagnostic in &diagnostics {
+                eprintln!("{path}: {diagnostic}");
+            }
+
+            if diagnostics.iter().any(Diagnostic::is_error) {
+                std::process::exit(1);
+            }
+            println!("{path} is valid.");
+        }
+        Command::Schema { output_path } => match output_path {
+            Some(path) => std::fs::write(&path, Config::json_schema())
+                .unwrap_or_else(|e| fail(format!("{}: {e}", path.display()))),
+            None => println!("{}", Config::json_schema()),
+        },
+        Command::ListFormatters => {
+            for formatter in TextFormatterType::value_variants() {
+                println!("{formatter:?}\t{}", formatter.description());
+            }
+        }
+        Command::ListSources => {
+            for source in TextSourceType::value_variants() {
+                println!("{source:?}\t{}", source.description());
+            }
+        }
+    }
 }
@@ -0,0 +1,30 @@
+/// Bibliographic information about a text, as found in its TEI header.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Metadata {
+    pub title: Option<String>,
+    pub author: Option<String>,
+    pub editors: Vec<String>,
+    pub edition: Option<String>,
+    /// Printed edition the digital text was made from
+    pub source: Option<String>,
+    pub responsibilities: Vec<Responsibility>,
+    pub licence: Option<String>,
+}
+
+/// Who did what for the digital edition, e.g. "converted to TEI" by "Perseus Project".
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Responsibility {
+    pub resp: String,
+    pub name: String,
+}
+
+impl Metadata {
+    /// Whether there is anything worth telling the reader about the edition.
+    pub fn has_edition_notes(&self) -> bool {
+        !self.editors.is_empty()
+            || self.edition.is_some()
+            || self.source.is_some()
+            || !self.responsibilities.is_empty()
+            || self.licence.is_some()
+    }
+}
@@ -0,0 +1,90 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// How the numbers of a counter are written.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub enum NumeralStyle {
+    Arabic,
+    Roman,
+    /// Alphabetic Greek numerals, e.g. `ρξθʹ`
+    Greek,
+}
+
+impl NumeralStyle {
+    pub fn format(self, number: usize) -> String {
+        match self {
+            Self::Arabic => number.to_string(),
+            Self::Roman => roman(number),
+            Self::Greek => greek(number),
+        }
+    }
+
+    /// LaTeX command printing the current value of a counter in this style.
+    pub fn latex_counter(self, counter: &str) -> String {
+        match self {
+            Self::Arabic => format!(r"\arabic{{{counter}}}"),
+            Self::Roman => format!(r"\Roman{{{counter}}}"),
+            Self::Greek => format!(r"\greekalpha{{{counter}}}"),
+        }
+    }
+}
+
+const ROMAN: [(usize, &str); 13] = [
+    (1000, "M"),
+    (900, "CM"),
+    (500, "D"),
+    (400, "CD"),
+    (100, "C"),
+    (90, "XC"),
+    (50, "L"),
+    (40, "XL"),
+    (10, "X"),
+    (9, "IX"),
+    (5, "V"),
+    (4, "IV"),
+    (1, "I"),
+];
+
+/// Upper case Roman numeral, there is no numeral for 0, so it is rendered as an empty string.
+pub fn roman(mut number: usize) -> String {
+    let mut numeral = String::new();
+    for (value, digits) in ROMAN {
+        while number >= value {
+            numeral.push_str(digits);
+            number -= value;
+        }
+    }
+
+    numeral
+}
+
+const GREEK_UNITS: [&str; 9] = ["α", "β", "γ", "δ", "ε", "ϛ", "ζ", "η", "θ"];
+const GREEK_TENS: [&str; 9] = ["ι", "κ", "λ", "μ", "ν", "ξ", "ο", "π", "ϙ"];
+const GREEK_HUNDREDS: [&str; 9] = ["ρ", "σ", "τ", "υ", "φ", "χ", "ψ", "ω", "ϡ"];
+
+/// Alphabetic Greek numeral with a trailing keraia, e.g. `ρξθʹ` for 169,
+/// thousands are marked with a lower left keraia, e.g. `͵αωʹ` for 1800.
+/// Numbers are taken modulo 10000 and 0 is rendered as an empty string.
+pub fn greek(number: usize) -> String {
+    let mut numeral = String::new();
+    let thousands = number / 1000 % 10;
+    if thousands != 0 {
+        numeral.push('͵');
+        numeral.push_str(GREEK_UNITS[thousands - 1]);
+    }
+
+    for (digit, letters) in [
+        (number / 100 % 10, GREEK_HUNDREDS),
+        (number / 10 % 10, GREEK_TENS),
+        (number % 10, GREEK_UNITS),
+    ] {
+        if digit != 0 {
+            numeral.push_str(letters[digit - 1]);
+        }
+    }
+
+    if !numeral.is_empty() {
+        numeral.push('ʹ');
+    }
+    numeral
+}
@@ -0,0 +1,293 @@
+use crate::formatters::latex::{BACK_MATTER_MARKER, WORK_MARKER};
+use regex::Regex;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use thiserror::Error;
+
+// The TOC, `\Ifthispageodd` and mparhack all need a few runs to settle
+const MAX_RUNS: usize = 5;
+
+// Files that decide whether another run can change the output
+const CROSS_REFERENCE_EXTENSIONS: [&str; 2] = ["aux", "toc"];
+
+const AUXILIARY_EXTENSIONS: [&str; 8] = [
+    "aux",
+    "log",
+    "toc",
+    "out",
+    "fls",
+    "fdb_latexmk",
+    "xdv",
+    "synctex.gz",
+];
+
+#[derive(Clone, Copy, Debug, clap::ValueEnum)]
+pub enum Engine {
+    Latexmk,
+    Xelatex,
+    Tectonic,
+}
+
+impl Engine {
+    /// First engine from the preferred order which can be found in `PATH`.
+    pub fn detect() -> Option<Self> {
+        [Self::Latexmk, Self::Xelatex, Self::Tectonic]
+            .into_iter()
+            .find(|engine| is_in_path(engine.program()))
+    }
+
+    fn program(&self) -> &'static str {
+        match self {
+            Self::Latexmk => "latexmk",
+            Self::Xelatex => "xelatex",
+            Self::Tectonic => "tectonic",
+        }
+    }
+
+    fn command(&self, tex_file: &Path) -> Command {
+        let mut command = Command::new(self.program());
+        match self {
+            Self::Latexmk => {
+                command.args(["-xelatex", "-interaction=nonstopmode", "-file-line-error"]);
+            }
+            Self::Xelatex => {
+                command.args(["-interaction=nonstopmode", "-file-line-error"]);
+            }
+            Self::Tectonic => {
+                // Tectonic reruns by itself and keeps no auxiliary files by default
+                command.arg("--keep-logs");
+            }
+        }
+
+        command
+            .arg(tex_file)
+            // Keep TeX from wrapping log lines, which would break error parsing
+            .env("max_print_line", "10000");
+        command
+    }
+
+    // Engines which do not rerun on their own have to be called until cross-references settle
+    fn needs_reruns(&self) -> bool {
+        matches!(self, Self::Xelatex)
+    }
+}
+
+fn is_in_path(program: &str) -> bool {
+    std::env::var_os("PATH")
+        .map(|paths| std::env::split_paths(&paths).any(|dir| dir.join(program).is_file()))
+        .unwrap_or(false)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LatexError {
+    /// Title of the work which produced the offending line, `None` for front and back matter
+    pub work: Option<String>,
+    pub line: usize,
+    pub message: String,
+}
+
+impl std::fmt::Display for LatexError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.work {
+            Some(work) => write!(f, "line {} (in {}): {}", self.line, work, self.message),
+            None => write!(f, "line {} (outside of works): {}", self.line, self.message),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BoxProblem {
+    /// Text sticking out of the line or page, e.g. a word which could not be broken
+    Overfull,
+    /// Spaces stretched too much, e.g. before a word which could not be broken
+    Underfull,
+}
+
+/// Line or page which TeX could not set well, as reported in the log.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BadBox {
+    /// Title of the work which produced the offending line, `None` for front and back matter
+    pub work: Option<String>,
+    pub line: usize,
+    pub problem: BoxProblem,
+    /// How much too wide or how bad, e.g. `12.3pt too wide` or `badness 10000`
+    pub detail: String,
+}
+
+impl std::fmt::Display for BadBox {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let problem = match self.problem {
+            BoxProblem::Overfull => "overfull",
+            BoxProblem::Underfull => "underfull",
+        };
+        match &self.work {
+            Some(work) => write!(
+                f,
+                "line {} (in {}): {problem} box, {}",
+                self.line, work, self.detail
+            ),
+            None => write!(
+                f,
+                "line {} (outside of works): {problem} box, {}",
+                self.line, self.detail
+            ),
+        }
+    }
+}
+
+/// PDF written by a LaTeX engine together with the lines it could not set well.
+pub struct Compilation {
+    pub pdf: PathBuf,
+    pub bad_boxes: Vec<BadBox>,
+}
+
+#[derive(Error, Debug)]
+pub enum PdfError {
+    #[error("no LaTeX engine found, install latexmk, xelatex or tectonic")]
+    NoEngine,
+    #[error("could not run {0}")]
+    EngineNotRunnable(&'static str),
+    #[error("LaTeX reported errors:\n{}", .0.iter().map(|e| e.to_string()).collect::<Vec<_>>().join("\n"))]
+    CompilationFailed(Vec<LatexError>),
+    #[error("LaTeX failed without reporting an error, see the log file")]
+    UnknownFailure,
+    #[error("the file could not be opened")]
+    FileSystemError,
+}
+
+/// Compiles a `.tex` file produced by the LaTeX formatter and returns the path of the PDF.
+pub fn compile(tex_path: &Path, engine: Engine) -> Result<Compilation, PdfError> {
+    let tex = std::fs::read_to_string(tex_path).map_err(|_| PdfError::FileSystemError)?;
+    let directory = tex_path
+        .parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    let tex_file = tex_path.file_name().ok_or(PdfError::FileSystemError)?;
+
+    let mut previous_references = None;
+    for run in 1..=MAX_RUNS {
+        let output = engine
+            .command(Path::new(tex_file))
+            .current_dir(directory)
+            .output()
+            .map_err(|_| PdfError::EngineNotRunnable(engine.program()))?;
+
+        if !output.status.success() {
+            let engine_output = [output.stdout, output.stderr].concat();
+            let errors = locate_errors(&String::from_utf8_lossy(&engine_output), &tex);
+            return Err(if errors.is_empty() {
+                PdfError::UnknownFailure
+            } else {
+                PdfError::CompilationFailed(errors)
+            });
+        }
+
+        if !engine.needs_reruns() {
+            break;
+        }
+
+        let references = read_cross_references(tex_path);
+        if previous_references.as_ref() == Some(&references) {
+            break;
+        }
+
+        if run == MAX_RUNS {
+            eprintln!("Cross-references did not settle after {MAX_RUNS} runs.");
+        }
+
+        previous_references = Some(references);
+    }
+
+    // Only the log of the last run tells about the final layout
+    let log = std::fs::read(tex_path.with_extension("log")).unwrap_or_default();
+    let bad_boxes = locate_bad_boxes(&String::from_utf8_lossy(&log), &tex);
+
+    clean_auxiliary_files(tex_path);
+    Ok(Compilation {
+        pdf: tex_path.with_extension("pdf"),
+        bad_boxes,
+    })
+}
+
+fn read_cross_references(tex_path: &Path) -> Vec<Option<Vec<u8>>> {
+    CROSS_REFERENCE_EXTENSIONS
+        .iter()
+        .map(|extension| std::fs::read(tex_path.with_extension(extension)).ok())
+        .collect()
+}
+
+fn clean_auxiliary_files(tex_path: &Path) {
+    for extension in AUXILIARY_EXTENSIONS {
+        // Missing files are fine, not every engine creates all of them
+        let _ = std::fs::remove_file(tex_path.with_extension(extension));
+    }
+}
+
+/// Finds `file:line: message` errors in the engine output and maps them
+/// to the works of the generated document.
+pub fn locate_errors(engine_output: &str, tex: &str) -> Vec<LatexError> {
+    let error_regex = Regex::new(r"(?m)^(?:error: )?\S*\.tex:(\d+): (.+)$").unwrap();
+    let work_starts = work_starts(tex);
+
+    error_regex
+        .captures_iter(engine_output)
+        .map(|captures| {
+            let line = captures[1].parse().unwrap();
+
+            LatexError {
+                work: work_at(&work_starts, line),
+                line,
+                message: captures[2].trim().to_string(),
+            }
+        })
+        .collect()
+}
+
+/// Finds overfull and underfull box warnings in the log and maps them
+/// to the works of the generated document.
+pub fn locate_bad_boxes(log: &str, tex: &str) -> Vec<BadBox> {
+    let box_regex = Regex::new(
+        r"(?m)^(Overfull|Underfull) \\[hv]box \(([^)]*)\) (?:in paragraph |in alignment |detected )?at lines? (\d+)",
+    )
+    .unwrap();
+    let work_starts = work_starts(tex);
+
+    box_regex
+        .captures_iter(log)
+        .map(|captures| {
+            let line = captures[3].parse().unwrap();
+
+            BadBox {
+                work: work_at(&work_starts, line),
+                line,
+                problem: match &captures[1] {
+                    "Overfull" => BoxProblem::Overfull,
+                    _ => BoxProblem::Underfull,
+                },
+                detail: captures[2].to_string(),
+            }
+        })
+        .collect()
+}
+
+// Lines where works and the back matter start, with the titles of the works
+fn work_starts(tex: &str) -> Vec<(usize, Option<String>)> {
+    let mut work_starts = Vec::new();
+    for (i, line) in tex.lines().enumerate() {
+        if let Some(work) = line.strip_prefix(WORK_MARKER) {
+            work_starts.push((i + 1, Some(work.to_string())));
+        } else if line == BACK_MATTER_MARKER {
+            work_starts.push((i + 1, None));
+        }
+    }
+
+    work_starts
+}
+
+fn work_at(work_starts: &[(usize, Option<String>)], line: usize) -> Option<String> {
+    work_starts
+        .iter()
+        .take_while(|(start, _)| *start <= line)
+        .last()
+        .and_then(|(_, work)| work.clone())
+}
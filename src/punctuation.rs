@@ -0,0 +1,252 @@
+use crate::formatters::Language;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Marks which are never preceded by a space and are followed by one.
+/// The Greek question mark and ano teleia are the same characters as `;` and `·`
+/// once normalised, so both forms are listed.
+const MARKS: [char; 9] = [',', '.', ';', ':', '?', '!', '·', '\u{37E}', '\u{387}'];
+
+const ENQUOTE: [char; 9] = ['\\', 'e', 'n', 'q', 'u', 'o', 't', 'e', '{'];
+
+/// Apostrophes found after elided vowels in Greek sources.
+const APOSTROPHES: [char; 4] = ['\'', '’', '᾽', 'ʼ'];
+
+/// Quotation marks, the outer ones alternating with the inner ones by nesting depth.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub enum QuoteStyle {
+    /// “…” with ‘…’ inside
+    English,
+    /// «…» with “…” inside
+    Guillemets,
+    /// „…“ with ‚…‘ inside
+    German,
+    /// «…» with ‹…› inside
+    Greek,
+}
+
+impl QuoteStyle {
+    /// Opening and closing marks of a quotation inside `depth` others.
+    pub fn marks(self, depth: usize) -> [&'static str; 2] {
+        let [outer, inner] = match self {
+            Self::English => [["“", "”"], ["‘", "’"]],
+            Self::Guillemets => [["«", "»"], ["“", "”"]],
+            Self::German => [["„", "“"], ["‚", "‘"]],
+            Self::Greek => [["«", "»"], ["‹", "›"]],
+        };
+
+        if depth.is_multiple_of(2) {
+            outer
+        } else {
+            inner
+        }
+    }
+}
+
+/// Typographic conventions of a language, as followed by [`normalize`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Rules {
+    /// Marks preceded by a thin space, as in French typography
+    pub spaced_marks: String,
+    /// Whether dashes between clauses are set off by spaces instead of closed up
+    pub spaced_dashes: bool,
+    /// Marks of quotations, `\enquote` or straight double quotes in the text
+    pub quotes: QuoteStyle,
+    /// Mark printed after elided vowels, in Greek
+    pub elision_mark: Option<char>,
+}
+
+impl Rules {
+    pub fn default_for(language: &Language) -> Self {
+        match language {
+            Language::Latin => Self {
+                spaced_marks: String::new(),
+                spaced_dashes: false,
+                quotes: QuoteStyle::English,
+                elision_mark: None,
+            },
+            Language::Greek => Self {
+                spaced_marks: String::new(),
+                spaced_dashes: false,
+                quotes: QuoteStyle::Greek,
+                elision_mark: Some('’'),
+            },
+        }
+    }
+}
+
+/// Rules of a language replacing its defaults, e.g. `{ "spaced_marks": ";:?!" }`.
+#[derive(Default, Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct LanguageRules {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub spaced_marks: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub spaced_dashes: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quotes: Option<QuoteStyle>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub elision_mark: Option<char>,
+}
+
+/// Punctuation rules of each language, so that a config can be shared by books in both.
+#[derive(Default, Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct Punctuation {
+    #[serde(default)]
+    pub latin: LanguageRules,
+    #[serde(default)]
+    pub greek: LanguageRules,
+}
+
+impl Punctuation {
+    pub fn rules(&self, language: &Language) -> Rules {
+        let custom = match language {
+            Language::Latin => &self.latin,
+            Language::Greek => &self.greek,
+        };
+        let defaults = Rules::default_for(language);
+
+        Rules {
+            spaced_marks: custom.spaced_marks.clone().unwrap_or(defaults.spaced_marks),
+            spaced_dashes: custom.spaced_dashes.unwrap_or(defaults.spaced_dashes),
+            quotes: custom.quotes.unwrap_or(defaults.quotes),
+            elision_mark: custom.elision_mark.or(defaults.elision_mark),
+        }
+    }
+}
+
+/// Spacing and marks of LaTeX text made to follow `rules`, in a single pass.
+/// Commands are copied as they are, so `\;` or `\\` are not taken for punctuation,
+/// apart from `\enquote{...}`, which is replaced by the quotation marks of its depth.
+pub fn normalize(text: &str, rules: &Rules) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut normalized = String::with_capacity(text.len());
+    // Whether each open group is a quotation
+    let mut groups = Vec::new();
+    let mut quote_depth = 0;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let previous = i.checked_sub(1).map(|i| chars[i]);
+        let next = chars.get(i + 1).copied();
+
+        match c {
+            '\\' if chars[i..].starts_with(&ENQUOTE) => {
+                normalized.push_str(rules.quotes.marks(quote_depth)[0]);
+                groups.push(true);
+                quote_depth += 1;
+                i = skip_spaces(&chars, i + ENQUOTE.len());
+                continue;
+            }
+            '\\' => {
+                let end = command_end(&chars, i);
+                normalized.extend(&chars[i..end]);
+                i = end;
+                continue;
+            }
+            '{' => {
+                groups.push(false);
+                normalized.push(c);
+            }
+            '}' => {
+                if groups.pop() == Some(true) {
+                    quote_depth -= 1;
+                    trim_spaces(&mut normalized);
+                    normalized.push_str(rules.quotes.marks(quote_depth)[1]);
+                } else {
+                    normalized.push(c);
+                }
+            }
+            ' ' | '\t' => {
+                if !normalized.ends_with(' ') {
+                    normalized.push(' ');
+                }
+            }
+            '—' | '-' if c == '—' || chars[i..].starts_with(&['-', '-', '-']) => {
+                trim_spaces(&mut normalized);
+                normalized.push_str(if rules.spaced_dashes { "~--- " } else { "---" });
+                i = skip_spaces(&chars, i + if c == '—' { 1 } else { 3 });
+                continue;
+            }
+            '"' => {
+                // Straight quotes inside a quotation take the marks of its inner quotations
+                let opening = previous.is_none_or(|c| c.is_whitespace() || "([{".contains(c));
+                normalized.push_str(rules.quotes.marks(quote_depth)[usize::from(!opening)]);
+            }
+            c if APOSTROPHES.contains(&c)
+                && previous.is_some_and(is_greek_letter)
+                && next.is_none_or(char::is_whitespace)
+                && rules.elision_mark.is_some() =>
+            {
+                normalized.extend(rules.elision_mark);
+            }
+            c if MARKS.contains(&c) => {
+                let mark = match c {
+                    '\u{37E}' => ';',
+                    '\u{387}' => '·',
+                    c => c,
+                };
+                // Decimals and times, e.g. `1.5` or `10:30`
+                let in_number = ",.:".contains(mark)
+                    && previous.is_some_and(|c| c.is_ascii_digit())
+                    && next.is_some_and(|c| c.is_ascii_digit());
+
+                if !in_number {
+                    trim_spaces(&mut normalized);
+                    if rules.spaced_marks.contains(mark)
+                        && !normalized.ends_with(|c| MARKS.contains(&c))
+                    {
+                        normalized.push_str(r"\,");
+                    }
+                }
+                normalized.push(mark);
+                if !in_number && needs_space(&chars[i + 1..]) {
+                    normalized.push(' ');
+                }
+            }
+            c => normalized.push(c),
+        }
+
+        i += 1;
+    }
+
+    normalized
+}
+
+// Index after a command starting at `start`, e.g. `\textit` or `\&`
+fn command_end(chars: &[char], start: usize) -> usize {
+    let name = chars[start + 1..]
+        .iter()
+        .take_while(|c| c.is_ascii_alphabetic())
+        .count();
+
+    (start + 1 + name.max(1)).min(chars.len())
+}
+
+// Whether a space has to be put after a mark followed by `rest`
+fn needs_space(rest: &[char]) -> bool {
+    match rest.first() {
+        None => false,
+        Some(c) if c.is_whitespace() || MARKS.contains(c) || APOSTROPHES.contains(c) => false,
+        Some(c) if ")]}\"”’»›“‘".contains(*c) => false,
+        // Footnote marks stay attached to the text they follow
+        Some('\\') => !rest.starts_with(&['\\', 'f', 'o', 'o', 't', 'n', 'o', 't', 'e']),
+        Some(_) => true,
+    }
+}
+
+fn skip_spaces(chars: &[char], mut i: usize) -> usize {
+    while chars.get(i).is_some_and(|c| *c == ' ' || *c == '\t') {
+        i += 1;
+    }
+    i
+}
+
+fn trim_spaces(text: &mut String) {
+    let trimmed = text.trim_end_matches([' ', '\t']).len();
+    text.truncate(trimmed);
+}
+
+fn is_greek_letter(c: char) -> bool {
+    c.is_alphabetic() && matches!(c, '\u{370}'..='\u{3FF}' | '\u{1F00}'..='\u{1FFF}')
+}
@@ -0,0 +1,74 @@
+use crate::formatters::Work;
+use crate::text::TextNode;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+// Rough average for an A5 page of 12pt text, good enough to balance volumes
+pub const WORDS_PER_PAGE: usize = 250;
+
+/// How much text may go into a single volume of a series.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum VolumeBudget {
+    Words(usize),
+    /// Estimated from the number of words, see `WORDS_PER_PAGE`
+    Pages(usize),
+}
+
+impl VolumeBudget {
+    fn words(&self) -> usize {
+        match self {
+            Self::Words(words) => *words,
+            Self::Pages(pages) => pages * WORDS_PER_PAGE,
+        }
+    }
+}
+
+pub fn word_count(work: &Work) -> usize {
+    work.text.to_string().split_whitespace().count()
+}
+
+/// Splits works into volumes at work boundaries, keeping their order. A volume is closed
+/// when the next work would exceed the budget, so a single work longer than the budget
+/// gets a volume of its own.
+pub fn split_volumes(works: Vec<Work>, budget: VolumeBudget) -> Vec<Vec<Work>> {
+    let budget = budget.words();
+    let mut volumes: Vec<Vec<Work>> = Vec::new();
+    let mut volume_words = 0;
+
+    for work in works {
+        let words = word_count(&work);
+        match volumes.last_mut() {
+            Some(volume) if volume_words + words <= budget => {
+                volume.push(work);
+                volume_words += words;
+            }
+            _ => {
+                volumes.push(vec![work]);
+                volume_words = words;
+            }
+        }
+    }
+
+    volumes
+}
+
+/// Path of a file belonging to a series, e.g. `lucian-2.tex` for `lucian.tex` and suffix `2`.
+pub fn volume_path(path: &Path, suffix: &str) -> PathBuf {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let file_name = match path.extension() {
+        Some(extension) => format!("{stem}-{suffix}.{}", extension.to_string_lossy()),
+        None => format!("{stem}-{suffix}"),
+    };
+
+    path.with_file_name(file_name)
+}
+
+/// Titles of the works in each volume, in order.
+pub fn series_contents(volumes: &[Vec<Work>]) -> Vec<Vec<String>> {
+    volumes
+        .iter()
+        .map(|volume| volume.iter().map(|work| work.title.clone()).collect())
+        .collect()
+}
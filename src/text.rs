@@ -1,4 +1,7 @@
 use crate::config::FormatterConfig;
+use crate::escape::escape_latex;
+use crate::formatters::Counter;
+use crate::vocabulary::Word;
 use std::borrow::Cow;
 
 pub trait TextNode: std::fmt::Debug {
@@ -28,6 +31,14 @@ impl TextNode for &str {
     }
 }
 
+/// Part of a verse line shared between speakers, `<l part="I|M|F">`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinePart {
+    Initial,
+    Medial,
+    Final,
+}
+
 #[derive(Debug, Clone, Copy)]
 pub enum TextNodeKind {
     Book,
@@ -37,6 +48,14 @@ pub enum TextNodeKind {
     Subsection,
     Paragraph,
     Epigram,
+    Letter,
+    /// Greeting at the beginning or the end of a letter
+    Salute,
+    /// Place and date of writing of a letter
+    Dateline,
+    /// End of a letter, with a farewell, dateline and signature
+    Closer,
+    Signed,
     Note,
     Deleted,
     Label,
@@ -48,6 +67,11 @@ pub enum TextNodeKind {
     PersonName,
     PlaceName,
     Line,
+    /// Line set in from the others, e.g. the pentameter of an elegiac couplet
+    IndentedLine,
+    /// Continued where the previous part of the line ended
+    SplitLine(LinePart),
+    LineGroup,
     Simple,
     Corrected,
     Name,
@@ -55,6 +79,11 @@ pub enum TextNodeKind {
     TechnicalTerm,
     Speaker,
     DialogueEntry,
+    StageDirection,
+    CastList,
+    CastItem,
+    Role,
+    RoleDescription,
     Regularized,
     Ref,
     Date,
@@ -105,30 +134,53 @@ impl TextNode for TextParent {
             TextNodeKind::Apparatus => {}
             TextNodeKind::Date => {}
             TextNodeKind::Speaker => {
-                let mut text = String::from(r"\vspace{6pt}\Needspace{2\baselineskip}\textbf{");
-                text.push_str(&formatted);
-                text.push_str(r"}·\\");
-                formatted = text;
+                // Placed by the formatter, either centred or in the margin
+                formatted = format!(r"\speaker{{{formatted}}}");
             }
             TextNodeKind::DialogueEntry => {}
+            TextNodeKind::StageDirection => {
+                formatted = format!(r"\stage{{{formatted}}}");
+            }
+            TextNodeKind::CastList => {
+                let heading = match &self.name {
+                    Some(name) => name.format_for_latex(config),
+                    None => config.latex_word(Word::CastList),
+                };
+
+                formatted =
+                    format!("\n\\begin{{castlist}}{{{heading}}}\n{formatted}\\end{{castlist}}\n");
+            }
+            TextNodeKind::CastItem => {
+                formatted = format!("\\castitem{{{}}}\n", formatted.trim());
+            }
+            TextNodeKind::Role => {
+                formatted = format!(r"\textsc{{\MakeLowercase{{{formatted}}}}}");
+            }
+            TextNodeKind::RoleDescription => {
+                formatted = format!(r", \textit{{{formatted}}}");
+            }
             TextNodeKind::Symbol => {
                 let mut text = String::from(r"\textit{");
                 text.push_str(&formatted);
-                text.push_str("}");
+                text.push('}');
                 formatted = text;
             }
             TextNodeKind::Book => {
                 // Title etc. are taken from input parameters
             }
-            TextNodeKind::Chapter => {}
+            TextNodeKind::Chapter => {
+                if let Some(heading) = &self.name {
+                    let heading = heading.format_for_latex(config);
+                    let word = config.latex_word(Word::Chapter);
+                    formatted = format!("\n\\chapterheading{{{word}}}{heading}{formatted}");
+                }
+            }
             TextNodeKind::TechnicalTerm => {}
             TextNodeKind::Lemma => {}
             TextNodeKind::Section => {
-                let prename = match config.language {
-                    crate::formatters::Language::Latin => r"Liber \Roman{section}",
-                    crate::formatters::Language::Greek => r"Βιβλίος \greekalpha{section}",
-                }
-                .to_string();
+                let word = config.latex_word(Word::Book);
+                let number = config.numeral_style(Counter::Book).latex_counter("section");
+                let prename = format!("{word} {number}");
 
                 let full_name;
                 let full_name_nl;
@@ -136,7 +188,7 @@ impl TextNode for TextParent {
                     full_name = format!("{prename}. {name}");
                     full_name_nl = format!(r"{prename}.\\{name}");
                 } else {
-                    full_name = format!("{prename}");
+                    full_name = prename.clone();
                     full_name_nl = full_name.clone();
                 };
 
@@ -167,7 +219,37 @@ impl TextNode for TextParent {
             TextNodeKind::Paragraph => {
                 formatted.push_str("\n\n");
             }
-            TextNodeKind::Epigram => {}
+            TextNodeKind::Epigram => {
+                let heading = self
+                    .name
+                    .as_ref()
+                    .map(|name| name.format_for_latex(config))
+                    .unwrap_or_else(|| String::from("{}{}\n"));
+                formatted = format!(
+                    "\n\\begin{{epigram}}\n\\epigramheading{heading}{formatted}\\end{{epigram}}\n"
+                );
+            }
+            TextNodeKind::Letter => {
+                let word = config.latex_word(Word::Letter);
+                let heading = self
+                    .name
+                    .as_ref()
+                    .map(|name| name.format_for_latex(config))
+                    .unwrap_or_else(|| String::from("{}{}\n"));
+                formatted = format!("\n\\letterheading{{{word}}}{heading}{formatted}\n");
+            }
+            TextNodeKind::Salute => {
+                formatted = format!("\\salute{{{}}}\n", formatted.trim());
+            }
+            TextNodeKind::Dateline => {
+                formatted = format!("\\dateline{{{}}}\n", formatted.trim());
+            }
+            TextNodeKind::Closer => {
+                formatted = format!("\n\\begin{{closer}}\n{formatted}\\end{{closer}}\n");
+            }
+            TextNodeKind::Signed => {
+                formatted = format!("\\signed{{{}}}\n", formatted.trim());
+            }
             TextNodeKind::Note => {}
             TextNodeKind::Highlight => {}
             TextNodeKind::Deleted => {}
@@ -179,7 +261,10 @@ impl TextNode for TextParent {
                 text.push_str("} ");
                 formatted = text;
             }
-            TextNodeKind::Quote => {}
+            TextNodeKind::Quote => {
+                // Marks of the depth of the quotation are put by `punctuation::normalize`
+                formatted = format!(r"\enquote{{{}}}", formatted.trim());
+            }
             TextNodeKind::BlockQuote => {
                 let mut text = String::from(r"\begin{displayquote}");
                 text.push_str(&formatted);
@@ -189,14 +274,28 @@ impl TextNode for TextParent {
             TextNodeKind::Emphasis | TextNodeKind::Italics => {
                 let mut text = String::from(r"\textit{");
                 text.push_str(&formatted);
-                text.push_str("}");
+                text.push('}');
                 formatted = text;
             }
             TextNodeKind::PersonName => {}
             TextNodeKind::PlaceName => {}
             TextNodeKind::ReferencingString => {}
             TextNodeKind::Line => {
-                formatted.push_str("\n\\\\");
+                formatted = format!("\\verseline{{{formatted}}}\n");
+            }
+            TextNodeKind::SplitLine(part) => {
+                let part = match part {
+                    LinePart::Initial => 'I',
+                    LinePart::Medial => 'M',
+                    LinePart::Final => 'F',
+                };
+                formatted = format!("\\splitline{{{part}}}{{{formatted}}}\n");
+            }
+            TextNodeKind::IndentedLine => {
+                formatted = format!("\\verseline[\\verseindent]{{{formatted}}}\n");
+            }
+            TextNodeKind::LineGroup => {
+                formatted = format!("\n\\begin{{stanza}}\n{formatted}\\end{{stanza}}\n");
             }
             TextNodeKind::Simple => {}
             TextNodeKind::Choice => {}
@@ -206,12 +305,11 @@ impl TextNode for TextParent {
             TextNodeKind::Description => {}
         }
 
-        formatted = replace_et_ampersand(formatted);
-        fix_text(formatted)
+        formatted
     }
 }
 
-fn ensure_dot(str: &str) -> Cow<str> {
+fn ensure_dot(str: &str) -> Cow<'_, str> {
     if str.ends_with('.') || str.ends_with(". ") {
         Cow::Borrowed(str)
     } else {
@@ -250,7 +348,7 @@ impl TextNode for ParagraphNumber {
     fn format_for_latex(&self, config: &FormatterConfig) -> String {
         let mut text = String::from(r"\refnumber{");
         text.push_str(&self.0.format_for_latex(config));
-        text.push_str("}");
+        text.push('}');
         text
     }
 }
@@ -264,9 +362,103 @@ impl TextNode for LineNumber {
     }
 
     fn format_for_latex(&self, config: &FormatterConfig) -> String {
+        if !config.prints_line_number(&self.0) {
+            return String::new();
+        }
+
         let mut text = String::from(r"\refnumber{");
         text.push_str(&self.0.format_for_latex(config));
-        text.push_str("}");
+        text.push('}');
+        text
+    }
+}
+
+/// Number of a verse line, printed in the margin every few lines.
+#[derive(Debug, Clone)]
+pub struct VerseNumber(pub String);
+
+impl TextNode for VerseNumber {
+    fn to_string(&self) -> String {
+        self.0.clone()
+    }
+
+    fn format_for_latex(&self, config: &FormatterConfig) -> String {
+        if !config.prints_line_number(&self.0) {
+            return String::new();
+        }
+
+        format!(r"\versenumber{{{}}}", self.0.format_for_latex(config))
+    }
+}
+
+/// Heading of a chapter, an epigram or a letter, its number followed by the `<head>`,
+/// e.g. the name of the poet or the sender and addressee.
+/// Formatted as the two arguments of the heading macro of its parent.
+#[derive(Debug)]
+pub struct NumberedHeading {
+    /// Whose numeral style the number is written in
+    pub counter: Counter,
+    pub number: Option<String>,
+    pub head: Option<Box<dyn TextNode>>,
+}
+
+impl TextNode for NumberedHeading {
+    fn to_string(&self) -> String {
+        let head = self.head.as_ref().map(|head| head.to_string());
+        [self.number.clone(), head]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    fn format_for_latex(&self, config: &FormatterConfig) -> String {
+        // Numbers such as `12a` are printed as they are
+        let number = self
+            .number
+            .as_deref()
+            .map(|number| match number.parse() {
+                Ok(number) => escape_latex(&config.numeral_style(self.counter).format(number)),
+                Err(_) => number.format_for_latex(config),
+            })
+            .unwrap_or_default();
+        let head = self
+            .head
+            .as_ref()
+            .map(|head| head.format_for_latex(config))
+            .unwrap_or_default();
+
+        format!("{{{number}}}{{{}}}\n", head.trim())
+    }
+}
+
+/// Page of a printed edition, e.g. `<pb n="12" ed="Migne"/>`.
+#[derive(Debug, Clone)]
+pub struct PageBreak {
+    pub number: Option<String>,
+    /// Several editions may be paginated side by side
+    pub ed: Option<String>,
+    /// Reference to the scan of the page, not printed
+    pub facs: Option<String>,
+}
+
+impl TextNode for PageBreak {
+    fn to_string(&self) -> String {
+        self.number.clone().unwrap_or_default()
+    }
+
+    fn format_for_latex(&self, config: &FormatterConfig) -> String {
+        let Some(number) = &self.number else {
+            return String::new();
+        };
+
+        let reference = match &self.ed {
+            Some(ed) => format!("{ed} {number}"),
+            None => number.clone(),
+        };
+        let mut text = String::from(r"\refnumber{");
+        text.push_str(&reference.format_for_latex(config));
+        text.push('}');
         text
     }
 }
@@ -282,7 +474,7 @@ impl TextNode for MarginNote {
     fn format_for_latex(&self, config: &FormatterConfig) -> String {
         let mut text = String::from(r"\refnumber{");
         text.push_str(&self.0.format_for_latex(config));
-        text.push_str("}");
+        text.push('}');
         text
     }
 }
@@ -312,7 +504,7 @@ impl TextNode for Milestone {
         if let Some(number) = &self.number {
             let mut text = String::from(r"\refnumber{");
             text.push_str(&number.format_for_latex(config));
-            text.push_str("}");
+            text.push('}');
             text
         } else {
             String::new()
@@ -342,13 +534,12 @@ impl TextNode for Highlight {
     }
 }
 
-// "lost" does not look good when all other footnotes are in Latin.
-// On the other hand, when footnotes are in English, "lacuna" is still acceptable,
+// When footnotes are in English, "lacuna" is still acceptable,
 // although somewhat over-the-top (so perfect for this project)
-fn translate_gap_reason(reason: &str) -> &str {
+fn gap_reason_word(reason: &str) -> Option<Word> {
     match reason {
-        "lost" => "lacuna",
-        x => x,
+        "lost" => Some(Word::Lacuna),
+        _ => None,
     }
 }
 
@@ -360,71 +551,30 @@ pub struct Gap {
 
 impl TextNode for Gap {
     fn to_string(&self) -> String {
+        let config = FormatterConfig::default();
         format!(
             "{} [{}]",
-            self.rend.as_ref().map(|x| x.as_str()).unwrap_or("[\\dots]"),
-            translate_gap_reason(&self.reason)
+            self.rend.as_deref().unwrap_or("[\\dots]"),
+            gap_reason_word(&self.reason).map_or(self.reason.as_str(), |word| config.word(word))
         )
     }
 
     fn format_for_latex(&self, config: &FormatterConfig) -> String {
+        let reason = match gap_reason_word(&self.reason) {
+            Some(word) => config.latex_word(word),
+            None => self.reason.format_for_latex(config),
+        };
+
         format!(
             "{}\\footnote{{{}}} ",
-            self.rend.as_ref().map(|x| x.as_str()).unwrap_or("[\\dots]"),
-            ensure_dot(translate_gap_reason(&self.reason.format_for_latex(config)))
+            self.rend
+                .as_deref()
+                .map_or_else(|| String::from(r"[\dots]"), escape_latex),
+            ensure_dot(&reason)
         )
     }
 }
 
-fn fix_punctuation(text: String, p: &str) -> String {
-    // A quick way to normalize spaces. Much faster than regexes.
-    text
-        // To avoid using `format!`
-        .replace(p, "\x00")
-        // Block replacing before quotation marks
-        .replace("\x00'", "\x01'")
-        .replace("\x00\"", "\x01\"")
-        // Block replacing before end of parentheses
-        .replace("\x00)", "\x01)")
-        .replace("\x00}", "\x01}")
-        .replace("\x00]", "\x01]")
-        // Normalize spaces everywhere
-        .replace("\x00", "\x00 ") // Double spaces are fixed later in `fix_text`
-        .replace(" \x00", "\x00")
-        // Restore before quotation marks
-        .replace("\x01", "\x00")
-        // Normalize spaces next to quotation marks and parentheses
-        .replace(" \x00'", "\x00'")
-        .replace(" \x00\"", "\x00\"")
-        .replace(" \x00)", "\x00)")
-        .replace(" \x00}", "\x00}")
-        .replace(" \x00]", "\x00]")
-        // Restore `p`
-        .replace("\x00", p)
-}
-
-pub fn fix_text(mut text: String) -> String {
-    text = fix_punctuation(text, ",");
-    text = fix_punctuation(text, ".");
-    text = fix_punctuation(text, "?");
-    text = fix_punctuation(text, "!");
-    text = fix_punctuation(text, ";");
-    text = fix_punctuation(text, ";"); // Greek question mark
-    text = fix_punctuation(text, ":");
-    text = fix_punctuation(text, "·");
-
-    text.replace("&gt;", "")
-        .replace("&lt;", "") // Remove junk
-        .replace(" — ", "---")
-        .replace("— ", "---")
-        .replace(" —", "---")
-        .replace(" ---", "---")
-        .replace("--- ", "---")
-        // Fix multiple spaces
-        .replace("  ", " ")
-        .replace("   ", " ")
-}
-
 const WORD_ENDS: [&str; 7] = [" ", ".", ",", "!", "?", ";", ":"];
 
 fn replace_word(text: String, word: &str, replacement: &str, terminator: &str) -> String {
@@ -460,17 +610,8 @@ fn replace_ae_oe(mut text: String) -> String {
     text
 }
 
-fn escape_special_chars(mut text: String) -> String {
-    text = text.replace('#', r"\#");
-    text = text.replace('\\', r"\\");
-    text = text.replace('&', r"\&");
-    text = text.replace('_', r"\_");
-    text
-}
-
-fn normalize_text(mut text: String) -> String {
-    text = escape_special_chars(text);
-    text = replace_et_ampersand(text);
-    text = replace_ae_oe(text);
-    text
+// Ligatures are made before escaping, so that they never touch LaTeX commands, and the
+// ampersand for `et` after it, so that its backslash is not escaped in turn
+fn normalize_text(text: String) -> String {
+    replace_et_ampersand(escape_latex(&replace_ae_oe(text)))
 }
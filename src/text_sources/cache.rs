@@ -0,0 +1,72 @@
+use super::GetTextError;
+use std::path::PathBuf;
+
+/// Downloaded documents kept on disk, so that a book can be rebuilt without network access.
+#[derive(Clone, Debug)]
+pub struct Cache {
+    dir: PathBuf,
+    refresh: bool,
+}
+
+impl Cache {
+    pub const DEFAULT_DIR: &'static str = ".egrapsa-cache";
+
+    pub fn new(dir: PathBuf) -> Self {
+        Self {
+            dir,
+            refresh: false,
+        }
+    }
+
+    /// Ignore cached documents and download them again.
+    pub fn refresh(self, refresh: bool) -> Self {
+        Self { refresh, ..self }
+    }
+
+    fn path(&self, source: &str, id: &str) -> PathBuf {
+        // Identifiers contain characters which are not allowed in file names on some systems
+        let file_name: String = id
+            .chars()
+            .map(|c| {
+                if c.is_alphanumeric() || c == '.' || c == '-' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+
+        self.dir.join(source).join(file_name + ".xml")
+    }
+
+    pub fn get(&self, source: &str, id: &str) -> Option<String> {
+        if self.refresh {
+            return None;
+        }
+
+        std::fs::read_to_string(self.path(source, id)).ok()
+    }
+
+    pub fn put(&self, source: &str, id: &str, text: &str) -> Result<(), GetTextError> {
+        let path = self.path(source, id);
+        std::fs::create_dir_all(path.parent().unwrap())
+            .and_then(|_| std::fs::write(path, text))
+            .map_err(|_| GetTextError::FileSystemError)
+    }
+
+    /// Returns the cached document or downloads it with `download` and caches it.
+    pub fn get_or_download(
+        &self,
+        source: &str,
+        id: &str,
+        download: impl FnOnce() -> Result<String, GetTextError>,
+    ) -> Result<String, GetTextError> {
+        if let Some(text) = self.get(source, id) {
+            return Ok(text);
+        }
+
+        let text = download()?;
+        self.put(source, id, &text)?;
+        Ok(text)
+    }
+}
@@ -0,0 +1,292 @@
+use super::tei::{read_document, read_tei_file, TeiFile};
+use super::{cts, xml_tree, GetTextError, GetTextResult, TextSource};
+use crate::metadata::Metadata;
+use crate::text::TextParent;
+use crate::urn::{CtsUrn, Passage, Reference};
+use quick_xml::{events::Event, Reader, Writer};
+use std::path::{Path, PathBuf};
+
+/// Local clone of a CapiTainS repository of TEI files, such as canonical-greekLit
+/// or canonical-latinLit of Perseus.
+///
+/// Files are found through the `__cts__.xml` files of text groups and works,
+/// passages are cut out of them locally.
+pub struct Canonical {
+    root: PathBuf,
+}
+
+impl Canonical {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    // Either the clone itself or its `data` directory may be given
+    fn data_dir(&self) -> PathBuf {
+        let data = self.root.join("data");
+        if data.is_dir() {
+            data
+        } else {
+            self.root.clone()
+        }
+    }
+
+    /// Path of the TEI file of an edition, or of the first edition of a work
+    /// which is not a translation.
+    pub fn edition_path(&self, urn: &CtsUrn) -> Result<PathBuf, GetTextError> {
+        let not_found = || GetTextError::NotInCatalog(urn.without_passage().to_string());
+        let work = urn.work.as_ref().ok_or_else(not_found)?;
+
+        let group_dir = self.data_dir().join(&urn.text_group);
+        let group = read_cts_file(&group_dir).ok_or_else(not_found)?;
+        if group.name != "textgroup" {
+            return Err(GetTextError::ParseError);
+        }
+
+        let work_dir = group_dir.join(work);
+        let work = read_cts_file(&work_dir).ok_or_else(not_found)?;
+        let work = cts::read_work(&work);
+        let edition = work
+            .editions
+            .iter()
+            .find(|edition| match &urn.version {
+                Some(version) => edition.version() == version,
+                None => !edition.translation,
+            })
+            .ok_or_else(not_found)?;
+
+        // Files are named after the URN of the edition without its namespace,
+        // e.g. `tlg0062.tlg001.perseus-grc2.xml`
+        let file_name = edition
+            .urn
+            .rsplit(':')
+            .next()
+            .ok_or(GetTextError::ParseError)?;
+        Ok(work_dir.join(format!("{file_name}.xml")))
+    }
+}
+
+fn read_cts_file(dir: &Path) -> Option<xml_tree::Element> {
+    let text = std::fs::read_to_string(dir.join("__cts__.xml")).ok()?;
+    xml_tree::parse(&text).ok()
+}
+
+/// How much of an element in the body is a part of the passage.
+enum Selection {
+    Whole,
+    /// Only the citable units within the passage, `Vec` is the reference of the element
+    Partial(Vec<String>),
+}
+
+// Citable units are the numbered textparts and lines
+fn citation_component(tag: &quick_xml::events::BytesStart) -> Option<String> {
+    let attribute = |name: &str| {
+        tag.try_get_attribute(name)
+            .ok()
+            .flatten()
+            .and_then(|attr| attr.unescape_value().ok())
+            .map(|value| value.to_string())
+    };
+
+    let is_citable = match tag.local_name().as_ref() {
+        b"div" => attribute("type").as_deref() == Some("textpart"),
+        b"l" => true,
+        _ => false,
+    };
+
+    is_citable.then(|| attribute("n")).flatten()
+}
+
+/// Element open within the body while its passage is selected.
+struct OpenElement {
+    selection: Selection,
+    /// Position of the start tag in the pending events, until it is written
+    pending: Option<usize>,
+    /// Kept only together with the element around it, like the speaker of a speech
+    dependent: bool,
+}
+
+/// Copy of a TEI document with only the given passage left in its body.
+///
+/// Headings of the units the passage starts or ends in are kept as well, and so are
+/// wrappers such as `<sp>` and `<lg>` around the selected lines, together with their speakers.
+fn select_passage(document: &str, passage: &Passage) -> Result<String, GetTextError> {
+    let reader = &mut Reader::from_str(document);
+    let mut writer = Writer::new(Vec::new());
+    // Elements open within the body, empty outside of it
+    let mut open: Vec<OpenElement> = Vec::new();
+    // Wrappers are written once something inside them is, and dropped if they stay empty
+    let mut pending = Vec::new();
+    let mut found = false;
+
+    loop {
+        let event = reader.read_event().map_err(|_| GetTextError::ParseError)?;
+        let Some(parent) = open.last() else {
+            match &event {
+                Event::Start(tag) if tag.local_name().as_ref() == b"body" => {
+                    open.push(OpenElement {
+                        selection: Selection::Partial(Vec::new()),
+                        pending: None,
+                        dependent: false,
+                    })
+                }
+                Event::Eof => break,
+                _ => {}
+            }
+            write(event, &mut writer)?;
+            continue;
+        };
+
+        let is_pending = parent.pending.is_some();
+        let element = match (&event, &parent.selection) {
+            (Event::Eof, _) => return Err(GetTextError::ParseError),
+            (Event::End(_), _) => {
+                let element = open.pop().unwrap();
+                match element.pending {
+                    Some(_) if element.dependent => pending.push(event),
+                    Some(start) => pending.truncate(start),
+                    None => write(event, &mut writer)?,
+                }
+                continue;
+            }
+            (Event::Start(_), Selection::Whole) => OpenElement {
+                selection: Selection::Whole,
+                pending: is_pending.then_some(pending.len()),
+                dependent: is_pending,
+            },
+            (_, Selection::Whole) | (Event::Text(_), Selection::Partial(_)) => {
+                // Only whitespace of partly selected elements, which keeps the layout readable
+                if let Event::Text(text) = &event {
+                    let is_partial = matches!(parent.selection, Selection::Partial(_));
+                    if is_partial && !text.iter().all(|byte| byte.is_ascii_whitespace()) {
+                        continue;
+                    }
+                }
+
+                if is_pending {
+                    pending.push(event);
+                } else {
+                    write(event, &mut writer)?;
+                }
+                continue;
+            }
+            (Event::Start(tag), Selection::Partial(components)) => match citation_component(tag) {
+                Some(component) => {
+                    let reference = Reference {
+                        components: [components.clone(), vec![component]].concat(),
+                        subreference: None,
+                    };
+
+                    if passage.contains(&reference) {
+                        found = true;
+                        OpenElement {
+                            selection: Selection::Whole,
+                            pending: None,
+                            dependent: false,
+                        }
+                    } else if reference.contains(&passage.start)
+                        || reference.contains(passage.end())
+                    {
+                        OpenElement {
+                            selection: Selection::Partial(reference.components),
+                            pending: None,
+                            dependent: false,
+                        }
+                    } else {
+                        reader
+                            .read_to_end(tag.name())
+                            .map_err(|_| GetTextError::ParseError)?;
+                        continue;
+                    }
+                }
+                None => match tag.local_name().as_ref() {
+                    b"div" => OpenElement {
+                        selection: Selection::Partial(components.clone()),
+                        pending: None,
+                        dependent: false,
+                    },
+                    b"head" => OpenElement {
+                        selection: Selection::Whole,
+                        pending: None,
+                        dependent: false,
+                    },
+                    b"speaker" => OpenElement {
+                        selection: Selection::Whole,
+                        pending: is_pending.then_some(pending.len()),
+                        dependent: is_pending,
+                    },
+                    _ => OpenElement {
+                        selection: Selection::Partial(components.clone()),
+                        pending: Some(pending.len()),
+                        dependent: false,
+                    },
+                },
+            },
+            (_, Selection::Partial(_)) => continue,
+        };
+
+        if element.pending.is_some() {
+            pending.push(event);
+        } else {
+            // Everything around a written element is written before it
+            for event in pending.drain(..) {
+                write(event, &mut writer)?;
+            }
+            for element in &mut open {
+                element.pending = None;
+            }
+            write(event, &mut writer)?;
+        }
+        open.push(element);
+    }
+
+    if !found {
+        return Err(GetTextError::PassageNotFound(passage.to_string()));
+    }
+
+    String::from_utf8(writer.into_inner()).map_err(|_| GetTextError::EncodingError)
+}
+
+fn write(event: Event, writer: &mut Writer<Vec<u8>>) -> Result<(), GetTextError> {
+    writer
+        .write_event(event)
+        .map_err(|_| GetTextError::ParseError)
+}
+
+impl TextSource for Canonical {
+    fn get_text(&self, id: &str) -> GetTextResult {
+        self.get_text_with_metadata(id).map(|(text, _)| text)
+    }
+
+    fn get_text_with_metadata(
+        &self,
+        id: &str,
+    ) -> Result<(TextParent, Option<Metadata>), GetTextError> {
+        if let Some(path) = id.strip_prefix("file:") {
+            return read_tei_file(path);
+        }
+
+        if !id.starts_with("urn:") {
+            return Err(GetTextError::InvalidIdentifier);
+        }
+
+        let urn: CtsUrn = id.parse().map_err(|_| GetTextError::InvalidIdentifier)?;
+        let path = self.edition_path(&urn)?;
+        let document = std::fs::read_to_string(path).map_err(|_| GetTextError::FileSystemError)?;
+        let document = match &urn.passage {
+            Some(passage) => select_passage(&document, passage)?,
+            None => document,
+        };
+
+        Ok(read_document(&document, &TeiFile))
+    }
+
+    // The repository is already on disk, so only the identifier is checked
+    fn fetch(&self, id: &str) -> Result<(), GetTextError> {
+        if id.starts_with("file:") {
+            return Ok(());
+        }
+
+        let urn: CtsUrn = id.parse().map_err(|_| GetTextError::InvalidIdentifier)?;
+        self.edition_path(&urn).map(|_| ())
+    }
+}
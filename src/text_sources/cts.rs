@@ -0,0 +1,235 @@
+use super::{cache::Cache, xml_tree, GetTextError};
+use crate::urn::{CtsUrn, Passage};
+use std::path::Path;
+
+/// Editions of a library, as listed by a CTS `GetCapabilities` reply.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Catalog {
+    pub text_groups: Vec<TextGroup>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextGroup {
+    /// e.g. `urn:cts:greekLit:tlg0062`
+    pub urn: String,
+    pub name: Option<String>,
+    pub works: Vec<CatalogWork>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CatalogWork {
+    /// e.g. `urn:cts:greekLit:tlg0062.tlg001`
+    pub urn: String,
+    pub title: Option<String>,
+    pub editions: Vec<Edition>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Edition {
+    /// e.g. `urn:cts:greekLit:tlg0062.tlg001.perseus-grc2`
+    pub urn: String,
+    pub label: Option<String>,
+    /// Translations are listed together with editions in the original language
+    pub translation: bool,
+    /// Number of levels of the citation scheme, e.g. 2 for book and line
+    pub citation_depth: usize,
+}
+
+impl Edition {
+    /// Last part of the URN, e.g. `perseus-grc2`.
+    pub fn version(&self) -> &str {
+        self.urn.rsplit('.').next().unwrap()
+    }
+}
+
+impl Catalog {
+    pub fn parse(text: &str) -> Result<Self, GetTextError> {
+        let root = xml_tree::parse(text).map_err(|_| GetTextError::ParseError)?;
+
+        // Text inventories are sent either bare or wrapped in a `GetCapabilities` reply
+        let inventory = match root.name.as_str() {
+            "TextInventory" => &root,
+            _ => root
+                .path(&["reply", "TextInventory"])
+                .ok_or(GetTextError::ParseError)?,
+        };
+
+        let text_groups = inventory
+            .children_named("textgroup")
+            .map(|group| TextGroup {
+                urn: group.attribute("urn").unwrap_or_default().to_string(),
+                name: group
+                    .child("groupname")
+                    .and_then(|name| name.non_empty_text()),
+                works: group.children_named("work").map(read_work).collect(),
+            })
+            .collect();
+
+        Ok(Self { text_groups })
+    }
+
+    pub fn from_path(path: &Path) -> Result<Self, GetTextError> {
+        Self::parse(&std::fs::read_to_string(path).map_err(|_| GetTextError::FileSystemError)?)
+    }
+
+    pub fn works(&self) -> impl Iterator<Item = &CatalogWork> {
+        self.text_groups.iter().flat_map(|group| group.works.iter())
+    }
+
+    pub fn edition(&self, urn: &str) -> Option<&Edition> {
+        self.works()
+            .flat_map(|work| work.editions.iter())
+            .find(|edition| edition.urn == urn)
+    }
+
+    /// Works of a text group or a single work, each with the first matching edition.
+    ///
+    /// Without `version` the first edition which is not a translation is picked,
+    /// otherwise the first whose version starts with it, e.g. `perseus-grc`.
+    pub fn select(
+        &self,
+        urn: &str,
+        version: Option<&str>,
+    ) -> Result<Vec<(&CatalogWork, &Edition)>, GetTextError> {
+        let selection: CtsUrn = urn
+            .parse()
+            .map_err(|_| GetTextError::NotInCatalog(urn.to_string()))?;
+        let works: Vec<_> = self
+            .works()
+            .filter(|work| {
+                work.urn
+                    .parse()
+                    .is_ok_and(|work_urn| selection.contains(&work_urn))
+            })
+            .collect();
+
+        if works.is_empty() {
+            return Err(GetTextError::NotInCatalog(urn.to_string()));
+        }
+
+        Ok(works
+            .into_iter()
+            .filter_map(|work| {
+                let edition = work.editions.iter().find(|edition| match version {
+                    Some(version) => edition.version().starts_with(version),
+                    None => !edition.translation,
+                })?;
+                Some((work, edition))
+            })
+            .collect())
+    }
+}
+
+// Also the root of the `__cts__.xml` files of works in CapiTainS repositories
+pub(super) fn read_work(element: &xml_tree::Element) -> CatalogWork {
+    CatalogWork {
+        urn: element.attribute("urn").unwrap_or_default().to_string(),
+        title: element
+            .child("title")
+            .and_then(|title| title.non_empty_text()),
+        editions: element
+            .child_elements()
+            .filter(|element| element.name == "edition" || element.name == "translation")
+            .map(read_edition)
+            .collect(),
+    }
+}
+
+fn read_edition(element: &xml_tree::Element) -> Edition {
+    let mut citations = Vec::new();
+    if let Some(mapping) = element.path(&["online", "citationMapping"]) {
+        mapping.descendants("citation", &mut citations);
+    }
+
+    Edition {
+        urn: element.attribute("urn").unwrap_or_default().to_string(),
+        label: element
+            .child("label")
+            .and_then(|label| label.non_empty_text()),
+        translation: element.name == "translation",
+        citation_depth: citations.len(),
+    }
+}
+
+/// Client of the CTS API of a library, used to look up editions and their citations.
+pub struct CtsApi {
+    api_url: String,
+    cache: Option<Cache>,
+}
+
+impl Default for CtsApi {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CtsApi {
+    pub const API_URL: &'static str = "https://scaife-cts.perseus.org/api/cts";
+
+    pub fn new() -> Self {
+        Self::with_api_url(Self::API_URL)
+    }
+
+    pub fn with_api_url(api_url: &str) -> Self {
+        Self {
+            api_url: api_url.to_string(),
+            cache: None,
+        }
+    }
+
+    pub fn with_cache(self, cache: Option<Cache>) -> Self {
+        Self { cache, ..self }
+    }
+
+    fn request(&self, query: &str) -> Result<String, GetTextError> {
+        let download = || {
+            ureq::get(&format!("{}?{query}", self.api_url))
+                .call()
+                .map_err(|_| GetTextError::ConnectionError)?
+                .into_string()
+                .map_err(|_| GetTextError::EncodingError)
+        };
+
+        match &self.cache {
+            Some(cache) => cache.get_or_download("cts", query, download),
+            None => download(),
+        }
+    }
+
+    pub fn capabilities(&self) -> Result<Catalog, GetTextError> {
+        Catalog::parse(&self.request("request=GetCapabilities")?)
+    }
+
+    /// URNs of all passages of an edition at the given citation level, counted from 1.
+    pub fn valid_reff(&self, urn: &str, level: usize) -> Result<Vec<String>, GetTextError> {
+        let reply = self.request(&format!("request=GetValidReff&urn={urn}&level={level}"))?;
+        let root = xml_tree::parse(&reply).map_err(|_| GetTextError::ParseError)?;
+        let reff = root
+            .path(&["reply", "reff"])
+            .ok_or(GetTextError::ParseError)?;
+
+        Ok(reff.children_named("urn").map(|urn| urn.text()).collect())
+    }
+
+    /// URN of the whole edition with its full passage range, e.g. `...perseus-grc2:1.1-24.804`.
+    pub fn full_range(&self, edition: &Edition) -> Result<CtsUrn, GetTextError> {
+        let not_found = || GetTextError::NotInCatalog(edition.urn.clone());
+        let edition_urn: CtsUrn = edition.urn.parse().map_err(|_| not_found())?;
+
+        let passages = self
+            .valid_reff(&edition.urn, edition.citation_depth.max(1))?
+            .iter()
+            .map(|urn| urn.parse::<CtsUrn>().ok().and_then(|urn| urn.passage))
+            .collect::<Option<Vec<_>>>()
+            .ok_or(GetTextError::ParseError)?;
+        let (first, last) = passages
+            .first()
+            .zip(passages.last())
+            .ok_or_else(not_found)?;
+
+        Ok(edition_urn.with_passage(Passage {
+            start: first.start.clone(),
+            end: (first != last).then(|| last.end().clone()),
+        }))
+    }
+}
@@ -0,0 +1,273 @@
+use super::tei::{
+    expect_closing_tag, expect_eof, read_contents, read_document, read_starting_div, read_tei_file,
+    skip_prolog, TeiFile, TeiLayout,
+};
+use super::{cache::Cache, GetTextError, GetTextResult, TextSource};
+use crate::metadata::Metadata;
+use crate::text::{TextNodeKind, TextParent};
+use crate::urn::{CtsUrn, Passage};
+use quick_xml::Reader;
+use serde_json::Value;
+use std::cell::OnceCell;
+use url::Url;
+
+/// Passage of a document, wrapped in `<dts:wrapper>` inside an otherwise empty `<TEI>`.
+struct DtsFragment;
+
+impl TeiLayout for DtsFragment {
+    // Fragments have no header, their metadata comes from the collections endpoint
+    fn open(&self, reader: &mut Reader<&[u8]>, buf: &mut Vec<u8>) -> Option<Metadata> {
+        skip_prolog(reader, buf);
+        None
+    }
+
+    fn read_body(&self, reader: &mut Reader<&[u8]>, buf: &mut Vec<u8>) -> TextParent {
+        let wrapper = read_starting_div(reader, buf).to_owned();
+        // Older drafts of the specification call it `<dts:fragment>`
+        let name = wrapper.local_name();
+        if !matches!(name.as_ref(), b"wrapper" | b"fragment") {
+            panic!(
+                "Expected tag <dts:wrapper>, found <{}>",
+                String::from_utf8_lossy(wrapper.name().0)
+            );
+        }
+
+        reader.trim_text(false);
+        let (name, subtexts) = read_contents(reader, buf, &wrapper);
+        reader.trim_text(true);
+
+        // Takes the place of `<div type="edition">` of whole documents
+        TextParent {
+            name,
+            kind: TextNodeKind::Book,
+            subtexts,
+        }
+    }
+
+    fn close(&self, reader: &mut Reader<&[u8]>, buf: &mut Vec<u8>) {
+        expect_closing_tag(reader, buf, "TEI");
+        expect_eof(reader, buf);
+    }
+}
+
+/// URLs of the endpoints, as announced by the entry point of the API.
+struct Endpoints {
+    collections: Url,
+    navigation: Url,
+    documents: Url,
+}
+
+/// Any repository implementing the Distributed Text Services API.
+///
+/// Identifiers are CTS URNs, whose passage is requested as a `ref` or a `start`/`end` range,
+/// or `dts:<id>` for resources identified otherwise, which are always downloaded whole.
+pub struct Dts {
+    entry_url: String,
+    cache: Option<Cache>,
+    endpoints: OnceCell<Endpoints>,
+}
+
+impl Default for Dts {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Dts {
+    pub const ENTRY_URL: &'static str = "https://scaife-cts.perseus.org/api/dts";
+
+    pub fn new() -> Self {
+        Self::with_entry_url(Self::ENTRY_URL)
+    }
+
+    pub fn with_entry_url(entry_url: &str) -> Self {
+        Self {
+            entry_url: entry_url.to_string(),
+            cache: None,
+            endpoints: OnceCell::new(),
+        }
+    }
+
+    pub fn with_cache(self, cache: Option<Cache>) -> Self {
+        Self { cache, ..self }
+    }
+
+    fn download(url: &Url) -> Result<String, GetTextError> {
+        ureq::get(url.as_str())
+            .call()
+            .map_err(|_| GetTextError::ConnectionError)?
+            .into_string()
+            .map_err(|_| GetTextError::EncodingError)
+    }
+
+    fn download_json(url: &Url) -> Result<Value, GetTextError> {
+        serde_json::from_str(&Self::download(url)?).map_err(|_| GetTextError::ParseError)
+    }
+
+    fn endpoints(&self) -> Result<&Endpoints, GetTextError> {
+        if let Some(endpoints) = self.endpoints.get() {
+            return Ok(endpoints);
+        }
+
+        let entry_url = Url::parse(&self.entry_url).map_err(|_| GetTextError::ConnectionError)?;
+        let entry = Self::download_json(&entry_url)?;
+        // Endpoints are usually given relative to the entry point
+        let endpoint = |name: &str| {
+            entry[name]
+                .as_str()
+                .and_then(|url| entry_url.join(url).ok())
+                .ok_or(GetTextError::ParseError)
+        };
+
+        let endpoints = Endpoints {
+            collections: endpoint("collections")?,
+            navigation: endpoint("navigation")?,
+            documents: endpoint("documents")?,
+        };
+        Ok(self.endpoints.get_or_init(|| endpoints))
+    }
+
+    fn cached(
+        &self,
+        key: &str,
+        download: impl FnOnce() -> Result<String, GetTextError>,
+    ) -> Result<String, GetTextError> {
+        match &self.cache {
+            Some(cache) => cache.get_or_download("dts", key, download),
+            None => download(),
+        }
+    }
+
+    fn document(&self, resource: &str, passage: Option<&Passage>) -> Result<String, GetTextError> {
+        let key = match passage {
+            Some(passage) => format!("{resource}:{passage}"),
+            None => resource.to_string(),
+        };
+
+        self.cached(&key, || {
+            let mut url = self.endpoints()?.documents.clone();
+            url.query_pairs_mut().append_pair("id", resource);
+            if let Some(passage) = passage {
+                let mut query = url.query_pairs_mut();
+                match &passage.end {
+                    Some(end) => query
+                        .append_pair("start", &passage.start.to_string())
+                        .append_pair("end", &end.to_string()),
+                    None => query.append_pair("ref", &passage.start.to_string()),
+                };
+            }
+
+            Self::download(&url)
+        })
+    }
+
+    /// Title and author of a resource, as described by the collections endpoint.
+    pub fn collection_metadata(&self, resource: &str) -> Result<Metadata, GetTextError> {
+        let collection = self.cached(&format!("collection:{resource}"), || {
+            let mut url = self.endpoints()?.collections.clone();
+            url.query_pairs_mut().append_pair("id", resource);
+            Self::download(&url)
+        })?;
+        let collection: Value =
+            serde_json::from_str(&collection).map_err(|_| GetTextError::ParseError)?;
+
+        // Dublin Core values may be single strings or lists of them
+        let first_string = |value: &Value| match value {
+            Value::Array(values) => values.first().and_then(Value::as_str).map(String::from),
+            value => value.as_str().map(String::from),
+        };
+        let dublin_core = collection
+            .get("dublinCore")
+            .or_else(|| collection.get("dts:dublincore"));
+        let author = dublin_core.and_then(|dublin_core| {
+            dublin_core
+                .get("creator")
+                .or_else(|| dublin_core.get("dc:creator"))
+                .and_then(first_string)
+        });
+
+        Ok(Metadata {
+            title: collection.get("title").and_then(first_string),
+            author,
+            ..Default::default()
+        })
+    }
+
+    /// References of all passages of a resource at the given citation level, counted from 1.
+    pub fn references(&self, resource: &str, level: usize) -> Result<Vec<String>, GetTextError> {
+        let mut url = self.endpoints()?.navigation.clone();
+        url.query_pairs_mut()
+            .append_pair("id", resource)
+            .append_pair("down", &level.to_string());
+        let navigation = Self::download_json(&url)?;
+
+        // Members are plain references in older drafts of the specification
+        let members = navigation["member"]
+            .as_array()
+            .ok_or(GetTextError::ParseError)?;
+        members
+            .iter()
+            .map(|member| {
+                member
+                    .get("ref")
+                    .unwrap_or(member)
+                    .as_str()
+                    .map(String::from)
+                    .ok_or(GetTextError::ParseError)
+            })
+            .collect()
+    }
+}
+
+impl TextSource for Dts {
+    fn get_text(&self, id: &str) -> GetTextResult {
+        self.get_text_with_metadata(id).map(|(text, _)| text)
+    }
+
+    fn get_text_with_metadata(
+        &self,
+        id: &str,
+    ) -> Result<(TextParent, Option<Metadata>), GetTextError> {
+        if let Some(path) = id.strip_prefix("file:") {
+            return read_tei_file(path);
+        }
+
+        let (resource, passage) = resource(id)?;
+        let document = self.document(&resource, passage.as_ref())?;
+        if passage.is_none() {
+            return Ok(read_document(&document, &TeiFile));
+        }
+
+        let (text, _) = read_document(&document, &DtsFragment);
+        // The title is only a fallback for the config, so the text is usable without it
+        let metadata = self.collection_metadata(&resource).ok();
+        Ok((text, metadata))
+    }
+
+    fn fetch(&self, id: &str) -> Result<(), GetTextError> {
+        if id.starts_with("file:") {
+            return Ok(());
+        }
+
+        let (resource, passage) = resource(id)?;
+        self.document(&resource, passage.as_ref())?;
+        if passage.is_some() {
+            // Optional, as when the text is read
+            let _ = self.collection_metadata(&resource);
+        }
+
+        Ok(())
+    }
+}
+
+// Resource and passage named by a CTS URN or a `dts:<id>` identifier
+fn resource(id: &str) -> Result<(String, Option<Passage>), GetTextError> {
+    if let Some(resource) = id.strip_prefix("dts:") {
+        Ok((resource.to_string(), None))
+    } else if id.starts_with("urn:") {
+        let urn: CtsUrn = id.parse().map_err(|_| GetTextError::InvalidIdentifier)?;
+        Ok((urn.without_passage().to_string(), urn.passage))
+    } else {
+        Err(GetTextError::InvalidIdentifier)
+    }
+}
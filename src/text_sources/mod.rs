@@ -1,3 +1,4 @@
+use crate::metadata::Metadata;
 use crate::text::TextParent;
 use thiserror::Error;
 
@@ -11,12 +12,39 @@ pub enum GetTextError {
     ParseError,
     #[error("the file could not be opened")]
     FileSystemError,
+    #[error("the identifier is not supported by the text source")]
+    InvalidIdentifier,
+    #[error("{0} was not found in the catalog of the library")]
+    NotInCatalog(String),
+    #[error("passage {0} was not found in the text")]
+    PassageNotFound(String),
 }
 
+pub use tei::read_tei_file;
+
 type GetTextResult = Result<TextParent, GetTextError>;
 
 pub trait TextSource {
     fn get_text(&self, id: &str) -> GetTextResult;
+
+    /// Like `get_text`, but also returns the metadata of the edition if the source has any.
+    fn get_text_with_metadata(
+        &self,
+        id: &str,
+    ) -> Result<(TextParent, Option<Metadata>), GetTextError> {
+        Ok((self.get_text(id)?, None))
+    }
+
+    /// Stores the document of `id` in the cache as it was downloaded, without parsing it.
+    /// Sources reading local files have nothing to store.
+    fn fetch(&self, id: &str) -> Result<(), GetTextError>;
 }
 
+pub mod cache;
+pub mod canonical;
+pub mod cts;
+pub mod dts;
 pub mod scaife;
+mod tei;
+pub mod tei_header;
+mod xml_tree;
@@ -0,0 +1,459 @@
+use super::{tei_header, GetTextError};
+use crate::formatters::Counter;
+use crate::metadata::Metadata;
+use crate::text::{
+    Footnote, Gap, Highlight, LineNumber, LinePart, MarginNote, Milestone, NumberedHeading,
+    PageBreak, TextNode, TextNodeKind, TextParent, VerseNumber,
+};
+use quick_xml::{
+    events::{BytesEnd, BytesStart, Event},
+    name::QName,
+    Reader,
+};
+
+/// Where the text is within a TEI document, which differs between sources.
+pub(crate) trait TeiLayout {
+    /// Reads everything before the text, returning the metadata found on the way.
+    fn open(&self, reader: &mut Reader<&[u8]>, buf: &mut Vec<u8>) -> Option<Metadata>;
+
+    /// Reads the text itself, by default a single element such as `<div type="edition">`.
+    fn read_body(&self, reader: &mut Reader<&[u8]>, buf: &mut Vec<u8>) -> TextParent {
+        let starting_div = read_starting_div(reader, buf).to_owned();
+        reader.trim_text(false);
+        let text = read_text(reader, buf, starting_div);
+        reader.trim_text(true);
+
+        text
+    }
+
+    fn close(&self, reader: &mut Reader<&[u8]>, buf: &mut Vec<u8>);
+}
+
+/// Standalone TEI document with a header, as kept in the repositories of Perseus and others.
+pub(crate) struct TeiFile;
+
+impl TeiLayout for TeiFile {
+    fn open(&self, reader: &mut Reader<&[u8]>, buf: &mut Vec<u8>) -> Option<Metadata> {
+        skip_prolog(reader, buf);
+        expect_opening_tag(reader, buf, "teiHeader");
+        let metadata = tei_header::read_tei_header(reader, buf);
+        expect_opening_tag(reader, buf, "text");
+
+        Some(metadata)
+    }
+
+    // Plays may list their characters in `<front>`, which is put before the text
+    fn read_body(&self, reader: &mut Reader<&[u8]>, buf: &mut Vec<u8>) -> TextParent {
+        let tag = read_starting_div(reader, buf).to_owned();
+        let front = match tag.name().0 {
+            b"front" => {
+                reader.trim_text(false);
+                let (_, front) = read_contents(reader, buf, &tag);
+                reader.trim_text(true);
+                expect_opening_tag(reader, buf, "body");
+                front
+            }
+            b"body" => Vec::new(),
+            name => panic!(
+                "Expected tag <front> or <body>, found <{}>",
+                String::from_utf8_lossy(name)
+            ),
+        };
+
+        let starting_div = read_starting_div(reader, buf).to_owned();
+        reader.trim_text(false);
+        let mut text = read_text(reader, buf, starting_div);
+        reader.trim_text(true);
+
+        text.subtexts.splice(0..0, front);
+        text
+    }
+
+    fn close(&self, reader: &mut Reader<&[u8]>, buf: &mut Vec<u8>) {
+        expect_closing_tag(reader, buf, "body");
+        expect_closing_tag(reader, buf, "text");
+        expect_closing_tag(reader, buf, "TEI");
+        expect_eof(reader, buf);
+    }
+}
+
+pub(crate) fn read_document(text: &str, layout: &dyn TeiLayout) -> (TextParent, Option<Metadata>) {
+    let reader = &mut Reader::from_str(text);
+    reader.trim_text(true);
+    let buf = &mut Vec::new();
+
+    let metadata = layout.open(reader, buf);
+    let text = layout.read_body(reader, buf);
+    layout.close(reader, buf);
+
+    (text, metadata)
+}
+
+/// Text and header of a standalone TEI file, as given by `file:` identifiers to every source.
+pub fn read_tei_file(path: &str) -> Result<(TextParent, Option<Metadata>), GetTextError> {
+    let text = std::fs::read_to_string(path).map_err(|_| GetTextError::FileSystemError)?;
+    Ok(read_document(&text, &TeiFile))
+}
+
+/// Skips the XML declaration, processing instructions and comments up to the opening `<TEI>`.
+pub(crate) fn skip_prolog(reader: &mut Reader<&[u8]>, buf: &mut Vec<u8>) {
+    loop {
+        match reader.read_event_into(buf) {
+            Ok(Event::Decl(_) | Event::PI(_) | Event::Comment(_) | Event::DocType(_)) => {}
+            Ok(Event::Start(tag)) if tag.name().0 == b"TEI" => break,
+            Err(e) => panic!("Expected tag <TEI>, got error: {e}"),
+            ev => panic!("Missing tag <TEI>, got event: {ev:?}"),
+        }
+    }
+}
+
+pub(crate) fn expect_opening_tag<'a>(
+    reader: &mut Reader<&[u8]>,
+    buf: &'a mut Vec<u8>,
+    tag_name: &str,
+) -> BytesStart<'a> {
+    match reader.read_event_into(buf) {
+        Ok(Event::Start(e)) if e.name().0 == tag_name.as_bytes() => e,
+        Err(e) => panic!("Expected tag <{tag_name}>, got error: {e}"),
+        ev => panic!("Missing tag <{tag_name}>, got event: {ev:?}"),
+    }
+}
+
+pub(crate) fn skip_expect_tag(reader: &mut Reader<&[u8]>, buf: &mut Vec<u8>, tag_name: &str) {
+    let bytes_start = expect_opening_tag(reader, buf, tag_name);
+
+    reader
+        .read_to_end(bytes_start.name())
+        .map_err(|e| panic!("Could not read the whole <{tag_name}> tag, got error: {e}"))
+        .unwrap();
+}
+
+pub(crate) fn expect_closing_tag(reader: &mut Reader<&[u8]>, buf: &mut Vec<u8>, tag_name: &str) {
+    match reader.read_event_into(buf) {
+        Ok(Event::End(e)) if e.name().0 == tag_name.as_bytes() => (),
+        Err(e) => panic!("Expected tag </{tag_name}>, got error: {e}"),
+        ev => panic!("Missing tag </{tag_name}>, got event: {ev:?}"),
+    }
+}
+
+pub(crate) fn read_starting_div<'a>(
+    reader: &mut Reader<&[u8]>,
+    buf: &'a mut Vec<u8>,
+) -> BytesStart<'a> {
+    match reader.read_event_into(buf) {
+        Ok(Event::Start(tag)) => tag,
+        other => panic!("Expected opening <div> tag, found {:?}", other),
+    }
+}
+
+fn remove_unnecessary_whitespace(text: String) -> String {
+    // New lines there don't mean anything for the text, same with tabulation
+    text.replace('\n', " ").replace('\t', "")
+}
+
+pub(crate) fn read_text(
+    reader: &mut Reader<&[u8]>,
+    buf: &mut Vec<u8>,
+    start_tag: BytesStart,
+) -> TextParent {
+    read_nested_text(reader, buf, start_tag, false)
+}
+
+// `in_chapter` tells whether a chapter around the text already has a numbered heading
+fn read_nested_text(
+    reader: &mut Reader<&[u8]>,
+    buf: &mut Vec<u8>,
+    start_tag: BytesStart,
+    in_chapter: bool,
+) -> TextParent {
+    // Other divisions read as chapters, such as the actiones of a speech or the poems
+    // of a collection, are not headed, and neither are chapters within chapters
+    let is_headed_chapter = !in_chapter && is_chapter_div(&start_tag);
+    let (mut name, mut subtexts) =
+        read_nested_contents(reader, buf, &start_tag, in_chapter || is_headed_chapter);
+    let kind = get_text_kind(&start_tag);
+
+    let counter = match kind {
+        TextNodeKind::Chapter if is_headed_chapter => Some(Counter::Chapter),
+        TextNodeKind::Epigram => Some(Counter::Epigram),
+        TextNodeKind::Letter => Some(Counter::Letter),
+        _ => None,
+    };
+    let number = get_attr_val_opt(&start_tag, "n");
+    if let Some(counter) = counter.filter(|_| number.is_some() || name.is_some()) {
+        name = Some(Box::new(NumberedHeading {
+            counter,
+            number,
+            head: name,
+        }));
+    }
+
+    if let (
+        TextNodeKind::Line | TextNodeKind::IndentedLine | TextNodeKind::SplitLine(_),
+        Some(number),
+    ) = (kind, get_attr_val_opt(&start_tag, "n"))
+    {
+        subtexts.insert(0, Box::new(VerseNumber(number)));
+    }
+
+    TextParent {
+        name,
+        kind,
+        subtexts,
+    }
+}
+
+/// Heading of a section, if it has one, and its text.
+pub(crate) type Contents = (Option<Box<dyn TextNode>>, Vec<Box<dyn TextNode>>);
+
+/// Reads everything up to the closing tag of `start_tag`, returning the heading and the text.
+pub(crate) fn read_contents(
+    reader: &mut Reader<&[u8]>,
+    buf: &mut Vec<u8>,
+    start_tag: &BytesStart,
+) -> Contents {
+    read_nested_contents(reader, buf, start_tag, false)
+}
+
+fn read_nested_contents(
+    reader: &mut Reader<&[u8]>,
+    buf: &mut Vec<u8>,
+    start_tag: &BytesStart,
+    in_chapter: bool,
+) -> Contents {
+    let mut subtexts = Vec::<Box<dyn TextNode>>::new();
+    let mut name: Option<Box<dyn TextNode>> = None;
+    loop {
+        match reader.read_event_into(buf) {
+            Ok(Event::Start(tag)) => match name_to_str(&tag.name()).to_lowercase().as_str() {
+                "p" | "div" | "del" | "foreign" | "label" | "q" | "title" | "quote" | "l"
+                | "cit" | "said" | "add" | "corr" | "num" | "sp" | "speaker" | "sic" | "reg"
+                | "ref" | "date" | "app" | "lem" | "choice" | "abbr" | "ex" | "expan" | "desc"
+                | "persname" | "name" | "placename" | "rs" | "term" | "emph" | "lg" | "ab"
+                | "seg" | "stage" | "castlist" | "castgroup" | "castitem" | "role" | "roledesc"
+                | "opener" | "closer" | "salute" | "dateline" | "signed" => {
+                    let tag = tag.to_owned();
+                    let text = read_nested_text(reader, buf, tag, in_chapter);
+                    subtexts.push(Box::new(text));
+                }
+                "note" | "bibl" => {
+                    let tag = tag.to_owned();
+                    let text = read_nested_text(reader, buf, tag, in_chapter);
+                    subtexts.push(Box::new(Footnote(text.to_string())));
+                }
+                "gap" => {
+                    let tag = tag.to_owned();
+                    let text = read_nested_text(reader, buf, tag, in_chapter);
+                    subtexts.push(Box::new("[...]"));
+                    subtexts.push(Box::new(Footnote(text.to_string())));
+                }
+                "hi" => {
+                    let rend = get_attr_val(&tag, "rend");
+                    let tag = tag.to_owned();
+                    let text = read_nested_text(reader, buf, tag, in_chapter);
+                    subtexts.push(Box::new(Highlight {
+                        rend,
+                        text: Box::new(text),
+                    }));
+                }
+                "head" => {
+                    let tag = tag.to_owned();
+                    let text = read_nested_text(reader, buf, tag, in_chapter);
+                    name = Some(Box::new(text));
+                }
+                name => {
+                    panic!("Unexpected tag found inside section: <{}>", name)
+                }
+            },
+            Ok(Event::End(tag)) => {
+                ensure_tag_end(&tag, start_tag);
+                break;
+            }
+            Ok(Event::Text(content)) => subtexts.push(Box::new(remove_unnecessary_whitespace(
+                std::str::from_utf8(&content.into_inner())
+                    .unwrap()
+                    .to_string(),
+            ))),
+            Ok(Event::Empty(tag)) => subtexts.push(read_empty_tag(&tag)),
+            Err(e) => panic!("Expected text, got error: {e}"),
+            Ok(Event::Comment(_)) => {}
+            ev => panic!("Missing text, got event: {ev:?}"),
+        }
+    }
+
+    (name, subtexts)
+}
+
+fn is_chapter_div(tag: &BytesStart) -> bool {
+    name_to_str(&tag.name()) == "div"
+        && get_attr_val_opt(tag, "subtype")
+            .is_some_and(|subtype| subtype.eq_ignore_ascii_case("chapter"))
+}
+
+fn ensure_tag_end(tag: &BytesEnd, start_tag: &BytesStart) {
+    if tag.name() != start_tag.name() {
+        panic!(
+            "Expected closing tag {:?}, found {:?}",
+            start_tag.name(),
+            tag.name()
+        );
+    }
+}
+
+fn get_attr_val(tag: &BytesStart, name: &str) -> String {
+    std::str::from_utf8(&tag.try_get_attribute(name).unwrap().unwrap().value)
+        .unwrap()
+        .to_string()
+}
+
+fn get_attr_val_opt(tag: &BytesStart, name: &str) -> Option<String> {
+    tag.try_get_attribute(name)
+        .unwrap()
+        .map(|attr| std::str::from_utf8(&attr.value).unwrap().to_string())
+}
+
+pub(crate) fn expect_eof(reader: &mut Reader<&[u8]>, buf: &mut Vec<u8>) {
+    let event = reader.read_event_into(buf).unwrap();
+
+    if event != Event::Eof {
+        panic!("Expected EOF, found: {event:?}")
+    }
+}
+
+fn read_empty_tag(tag: &BytesStart) -> Box<dyn TextNode> {
+    match name_to_str(&tag.name()) {
+        // Sometimes <X /> appears for not reason,
+        // where X should never be an empty tag.
+        // Seems to be some junk.
+        "l" | "p" => Box::new(""),
+        "pb" => Box::new(PageBreak {
+            number: get_attr_val_opt(tag, "n"),
+            ed: get_attr_val_opt(tag, "ed"),
+            facs: get_attr_val_opt(tag, "facs"),
+        }),
+        "lb" => {
+            if let Some(x) = get_attr_val_opt(tag, "n") {
+                Box::new(LineNumber(x))
+            } else {
+                Box::new("")
+            }
+        }
+        "note" => Box::new(MarginNote(get_attr_val(tag, "n"))),
+        "gap" => {
+            let reason = get_attr_val(tag, "reason");
+            let rend = get_attr_val_opt(tag, "rend");
+            Box::new(Gap { reason, rend })
+        }
+        "milestone" => {
+            let unit = get_attr_val(tag, "unit");
+            let number = get_attr_val_opt(tag, "n");
+            let ed = get_attr_val_opt(tag, "ed");
+            let resp = get_attr_val_opt(tag, "resp");
+            Box::new(Milestone {
+                unit,
+                number,
+                ed,
+                resp,
+            })
+        }
+        "space" => Box::new(" "),
+        name => {
+            panic!("Unexpected empty tag found inside section: <{}/>", name)
+        }
+    }
+}
+
+fn name_to_str<'a>(name: &QName<'a>) -> &'a str {
+    std::str::from_utf8(name.0).unwrap()
+}
+
+fn get_text_kind(tag: &BytesStart) -> TextNodeKind {
+    match name_to_str(&tag.name()).to_lowercase().as_str() {
+        "head" | "foreign" | "quote" | "add" | "seg" => TextNodeKind::Simple,
+        "date" => TextNodeKind::Date,
+        "app" => TextNodeKind::Apparatus,
+        "lem" => TextNodeKind::Lemma,
+        "reg" => TextNodeKind::Regularized,
+        "ref" => TextNodeKind::Ref,
+        "choice" => TextNodeKind::Choice,
+        "abbr" => TextNodeKind::Abbreviated,
+        "ex" => TextNodeKind::Expanded,
+        "expan" => TextNodeKind::Expandable,
+        "sp" => TextNodeKind::DialogueEntry,
+        "stage" => TextNodeKind::StageDirection,
+        "castlist" => TextNodeKind::CastList,
+        "castgroup" => TextNodeKind::Simple,
+        "castitem" => TextNodeKind::CastItem,
+        "role" => TextNodeKind::Role,
+        "roledesc" => TextNodeKind::RoleDescription,
+        "opener" => TextNodeKind::Simple,
+        "salute" => TextNodeKind::Salute,
+        "dateline" => TextNodeKind::Dateline,
+        "closer" => TextNodeKind::Closer,
+        "signed" => TextNodeKind::Signed,
+        "sic" => TextNodeKind::Sic,
+        "speaker" => TextNodeKind::Speaker,
+        "num" => TextNodeKind::Symbol,
+        "corr" => TextNodeKind::Corrected,
+        "name" => TextNodeKind::Name,
+        "rs" => TextNodeKind::ReferencingString,
+        "desc" => TextNodeKind::Description,
+        "l" => {
+            let part = match get_attr_val_opt(tag, "part").as_deref() {
+                Some("I") => Some(LinePart::Initial),
+                Some("M") => Some(LinePart::Medial),
+                Some("F") => Some(LinePart::Final),
+                _ => None,
+            };
+            if let Some(part) = part {
+                return TextNodeKind::SplitLine(part);
+            }
+
+            // Pentameters of elegiac couplets are set in
+            let indented = get_attr_val_opt(tag, "rend").as_deref() == Some("indent")
+                || get_attr_val_opt(tag, "met").as_deref() == Some("pentameter");
+            if indented {
+                TextNodeKind::IndentedLine
+            } else {
+                TextNodeKind::Line
+            }
+        }
+        "lg" => TextNodeKind::LineGroup,
+        "label" => TextNodeKind::Label,
+        "title" => TextNodeKind::Italics,
+        "persname" => TextNodeKind::PersonName,
+        "placename" => TextNodeKind::PlaceName,
+        "term" => TextNodeKind::TechnicalTerm,
+        "emph" => TextNodeKind::Emphasis,
+        "hi" => TextNodeKind::Highlight,
+        "p" | "ab" => TextNodeKind::Paragraph,
+        // Speeches of dialogues are marked by their speakers, and `direct` is "unknown"
+        // unless given, so only speech explicitly marked as direct is quoted
+        "said" if get_attr_val_opt(tag, "who").is_some() => TextNodeKind::Paragraph,
+        "said" if get_attr_val_opt(tag, "direct").as_deref() == Some("true") => TextNodeKind::Quote,
+        "said" => TextNodeKind::Simple,
+        "gap" | "note" | "bibl" => TextNodeKind::Note,
+        "del" => TextNodeKind::Deleted,
+        "q" => TextNodeKind::Quote,
+        "cit" => TextNodeKind::BlockQuote,
+        "div" => match get_attr_val(tag, "type").to_lowercase().as_str() {
+            "edition" => TextNodeKind::Book,
+            "textpart" => match get_attr_val(tag, "subtype").to_lowercase().as_str() {
+                // section -> paragraph is correct, it's basically how Scaife treats sections
+                "epigram" => TextNodeKind::Epigram,
+                "letter" | "epistle" | "epistula" => TextNodeKind::Letter,
+                // No idea why "textpart" appears as "subtype" sometimes
+                "textpart" | "section" => TextNodeKind::Paragraph,
+                "book" => TextNodeKind::Section,
+                "chapter" => TextNodeKind::Chapter,
+                "actio" => TextNodeKind::Chapter,
+                "poem" | "hymn" | "oration" | "homily" => TextNodeKind::Chapter,
+                // Verse of First1KGreek and other OGL texts is often cited by line divisions
+                "line" | "verse" => TextNodeKind::Line,
+                name => panic!("Invalid div subtype for text kind: {name}"),
+            },
+            name => panic!("Invalid div type for text kind: {name}"),
+        },
+        name => panic!("Invalid tag type for text kind: {name}"),
+    }
+}
@@ -0,0 +1,68 @@
+use super::xml_tree::{read_element, Element};
+use crate::metadata::{Metadata, Responsibility};
+use quick_xml::Reader;
+
+/// Reads the rest of a `<teiHeader>` whose opening tag has just been read.
+pub fn read_tei_header(reader: &mut Reader<&[u8]>, buf: &mut Vec<u8>) -> Metadata {
+    // The header is small, so it is read into a tree first and queried afterwards
+    let mut header = Element::new("teiHeader");
+    read_element(reader, buf, &mut header)
+        .unwrap_or_else(|e| panic!("Could not read <teiHeader>, got error: {e}"));
+
+    let title_stmt = header.path(&["fileDesc", "titleStmt"]);
+    let title = title_stmt.and_then(|stmt| {
+        // Subtitles and the like are marked with `type`
+        stmt.children_named("title")
+            .find(|title| !matches!(title.attribute("type"), Some("sub" | "alt")))
+            .and_then(Element::non_empty_text)
+    });
+    let author = title_stmt
+        .and_then(|stmt| stmt.child("author"))
+        .and_then(Element::non_empty_text);
+    let editors = title_stmt
+        .map(|stmt| {
+            stmt.children_named("editor")
+                .filter_map(Element::non_empty_text)
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let edition = header
+        .path(&["fileDesc", "editionStmt", "edition"])
+        .and_then(Element::non_empty_text);
+    let source = header
+        .path(&["fileDesc", "sourceDesc"])
+        .and_then(Element::non_empty_text);
+
+    let mut resp_stmts = Vec::new();
+    header.descendants("respStmt", &mut resp_stmts);
+    let responsibilities = resp_stmts
+        .into_iter()
+        .filter_map(|stmt| {
+            let resp = stmt.child("resp")?.non_empty_text()?;
+            let name = ["persName", "name", "orgName"]
+                .iter()
+                .find_map(|name| stmt.child(name))?
+                .non_empty_text()?;
+            Some(Responsibility { resp, name })
+        })
+        .collect();
+
+    let mut licences = Vec::new();
+    header.descendants("licence", &mut licences);
+    let licence = licences.first().and_then(|licence| {
+        licence
+            .non_empty_text()
+            .or_else(|| licence.attribute("target").map(String::from))
+    });
+
+    Metadata {
+        title,
+        author,
+        editors,
+        edition,
+        source,
+        responsibilities,
+        licence,
+    }
+}
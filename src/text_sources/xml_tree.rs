@@ -0,0 +1,158 @@
+use quick_xml::{events::Event, Reader};
+
+// Small documents such as headers and catalogs are read into a tree and queried afterwards
+#[derive(Debug, Default)]
+pub(crate) struct Element {
+    pub name: String,
+    attributes: Vec<(String, String)>,
+    children: Vec<Node>,
+}
+
+#[derive(Debug)]
+pub(crate) enum Node {
+    Element(Element),
+    Text(String),
+}
+
+impl Element {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            ..Default::default()
+        }
+    }
+
+    pub fn child(&self, name: &str) -> Option<&Element> {
+        self.children_named(name).next()
+    }
+
+    pub fn child_elements(&self) -> impl Iterator<Item = &Element> {
+        self.children.iter().filter_map(|child| match child {
+            Node::Element(element) => Some(element),
+            Node::Text(_) => None,
+        })
+    }
+
+    pub fn children_named(&self, name: &str) -> impl Iterator<Item = &Element> {
+        let name = name.to_string();
+        self.child_elements()
+            .filter(move |element| element.name == name)
+    }
+
+    pub fn path(&self, path: &[&str]) -> Option<&Element> {
+        path.iter()
+            .try_fold(self, |element, name| element.child(name))
+    }
+
+    /// All descendant elements with the given name, in document order.
+    pub fn descendants<'a>(&'a self, name: &str, found: &mut Vec<&'a Element>) {
+        for child in &self.children {
+            if let Node::Element(element) = child {
+                if element.name == name {
+                    found.push(element);
+                }
+                element.descendants(name, found);
+            }
+        }
+    }
+
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn text(&self) -> String {
+        let mut text = String::new();
+        self.collect_text(&mut text);
+        text.split_whitespace().collect::<Vec<_>>().join(" ")
+    }
+
+    fn collect_text(&self, text: &mut String) {
+        for child in &self.children {
+            match child {
+                Node::Text(content) => {
+                    text.push_str(content);
+                    text.push(' ');
+                }
+                Node::Element(element) => element.collect_text(text),
+            }
+        }
+    }
+
+    pub fn non_empty_text(&self) -> Option<String> {
+        Some(self.text()).filter(|text| !text.is_empty())
+    }
+}
+
+fn local_name(name: &[u8]) -> String {
+    let name = std::str::from_utf8(name).unwrap();
+    name.rsplit(':').next().unwrap().to_string()
+}
+
+/// Reads the contents of `element` up to its closing tag, which has to be the next one.
+pub(crate) fn read_element(
+    reader: &mut Reader<&[u8]>,
+    buf: &mut Vec<u8>,
+    element: &mut Element,
+) -> Result<(), quick_xml::Error> {
+    loop {
+        match reader.read_event_into(buf)? {
+            Event::Start(tag) => {
+                let mut child = new_element(&tag);
+                read_element(reader, buf, &mut child)?;
+                element.children.push(Node::Element(child));
+            }
+            Event::Empty(tag) => element.children.push(Node::Element(new_element(&tag))),
+            Event::Text(content) => element.children.push(Node::Text(
+                content
+                    .unescape()
+                    .map(|text| text.to_string())
+                    .unwrap_or_default(),
+            )),
+            Event::End(_) => return Ok(()),
+            Event::Eof => return Err(quick_xml::Error::UnexpectedEof(element.name.clone())),
+            _ => {}
+        }
+    }
+}
+
+/// Reads a whole document into the tree of its root element.
+pub(crate) fn parse(text: &str) -> Result<Element, quick_xml::Error> {
+    let reader = &mut Reader::from_str(text);
+    reader.trim_text(true);
+    let buf = &mut Vec::new();
+
+    loop {
+        match reader.read_event_into(buf)? {
+            Event::Start(tag) => {
+                let mut root = new_element(&tag);
+                read_element(reader, buf, &mut root)?;
+                return Ok(root);
+            }
+            Event::Empty(tag) => return Ok(new_element(&tag)),
+            Event::Eof => return Err(quick_xml::Error::UnexpectedEof(String::from("document"))),
+            _ => {}
+        }
+    }
+}
+
+fn new_element(tag: &quick_xml::events::BytesStart) -> Element {
+    Element {
+        name: local_name(tag.name().0),
+        attributes: tag
+            .attributes()
+            .flatten()
+            .map(|attr| {
+                (
+                    local_name(attr.key.0),
+                    attr.unescape_value()
+                        .map(|value| value.to_string())
+                        .unwrap_or_default(),
+                )
+            })
+            .collect(),
+        children: Vec::new(),
+    }
+}
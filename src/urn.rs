@@ -0,0 +1,362 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error;
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum UrnError {
+    #[error("a CTS URN has to start with `urn:cts:`")]
+    MissingPrefix,
+    #[error("the namespace is missing")]
+    MissingNamespace,
+    #[error("the text group is missing")]
+    MissingTextGroup,
+    #[error("the work component `{0}` is not of the form `textgroup[.work[.version[.exemplar]]]`")]
+    InvalidWork(String),
+    #[error("the passage `{0}` is not of the form `reference[-reference]`")]
+    InvalidPassage(String),
+    #[error("too many components separated by `:`")]
+    TooManyComponents,
+}
+
+/// Position in the citation hierarchy of a text, e.g. `1.50` or `1.1@μῆνιν`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Reference {
+    /// e.g. `["1", "50"]` for book 1, line 50
+    pub components: Vec<String>,
+    pub subreference: Option<Subreference>,
+}
+
+/// Word within a passage, `@μῆνιν[2]` is its second occurrence.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Subreference {
+    pub text: String,
+    /// Counted from 1
+    pub index: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Passage {
+    pub start: Reference,
+    /// `None` for a single reference
+    pub end: Option<Reference>,
+}
+
+/// Parsed CTS URN, e.g. `urn:cts:greekLit:tlg0012.tlg001.perseus-grc2:1.1-1.303`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CtsUrn {
+    pub namespace: String,
+    pub text_group: String,
+    pub work: Option<String>,
+    pub version: Option<String>,
+    pub exemplar: Option<String>,
+    pub passage: Option<Passage>,
+}
+
+// Citation components are mostly numbers, possibly followed by letters, e.g. `12a`.
+// Named ones such as `pr` or `prologue` come before the numbered ones.
+fn compare_components(a: &str, b: &str) -> Ordering {
+    let split = |component: &str| {
+        let digits = component
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(component.len());
+        (
+            component[..digits].parse::<u64>().ok(),
+            component[digits..].to_string(),
+        )
+    };
+
+    match (split(a), split(b)) {
+        ((Some(a_number), a_rest), (Some(b_number), b_rest)) => {
+            a_number.cmp(&b_number).then_with(|| a_rest.cmp(&b_rest))
+        }
+        ((None, _), (Some(_), _)) => Ordering::Less,
+        ((Some(_), _), (None, _)) => Ordering::Greater,
+        _ => a.cmp(b),
+    }
+}
+
+impl Reference {
+    /// Whether `other` is this reference or lies within it, e.g. `1` contains `1.50`.
+    pub fn contains(&self, other: &Reference) -> bool {
+        self.components.len() <= other.components.len()
+            && self
+                .components
+                .iter()
+                .zip(&other.components)
+                .all(|(a, b)| compare_components(a, b) == Ordering::Equal)
+    }
+
+    // Compares only the components both references have
+    fn compare_common(&self, other: &Reference) -> Ordering {
+        self.components
+            .iter()
+            .zip(&other.components)
+            .map(|(a, b)| compare_components(a, b))
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or(Ordering::Equal)
+    }
+}
+
+impl Ord for Reference {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.compare_common(other)
+            .then_with(|| self.components.len().cmp(&other.components.len()))
+            .then_with(|| {
+                let key = |reference: &Self| {
+                    reference
+                        .subreference
+                        .as_ref()
+                        .map(|sub| (sub.text.clone(), sub.index))
+                };
+                key(self).cmp(&key(other))
+            })
+            // Keeps `1` and `01` apart, as equality does
+            .then_with(|| self.components.cmp(&other.components))
+    }
+}
+
+impl PartialOrd for Reference {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Passage {
+    pub fn end(&self) -> &Reference {
+        self.end.as_ref().unwrap_or(&self.start)
+    }
+
+    /// Whether the whole of `reference` lies within the passage, e.g. `1.1-1.303` contains `1.50`
+    /// but neither `1` nor `2.3`.
+    pub fn contains(&self, reference: &Reference) -> bool {
+        let after_start = match reference.compare_common(&self.start) {
+            Ordering::Greater => true,
+            Ordering::Equal => reference.components.len() >= self.start.components.len(),
+            Ordering::Less => false,
+        };
+        let before_end = match reference.compare_common(self.end()) {
+            Ordering::Less => true,
+            Ordering::Equal => reference.components.len() >= self.end().components.len(),
+            Ordering::Greater => false,
+        };
+
+        after_start && before_end
+    }
+
+    /// Whether the passage does not end before it starts, e.g. `2.1-1.5` is not ordered.
+    pub fn is_ordered(&self) -> bool {
+        self.start.compare_common(self.end()) != Ordering::Greater
+    }
+
+    pub fn contains_passage(&self, other: &Passage) -> bool {
+        self.contains(&other.start) && self.contains(other.end())
+    }
+}
+
+impl CtsUrn {
+    /// The same URN without its passage.
+    pub fn without_passage(&self) -> Self {
+        Self {
+            passage: None,
+            ..self.clone()
+        }
+    }
+
+    pub fn with_passage(&self, passage: Passage) -> Self {
+        Self {
+            passage: Some(passage),
+            ..self.clone()
+        }
+    }
+
+    /// Whether the URN names a single edition or translation, with or without a passage.
+    pub fn is_edition(&self) -> bool {
+        self.version.is_some()
+    }
+
+    /// Whether everything `other` refers to is also referred to by this URN,
+    /// e.g. a text group contains its works and `...:1.1-1.303` contains `...:1.50`.
+    pub fn contains(&self, other: &CtsUrn) -> bool {
+        let part_contains = |part: &Option<String>, other_part: &Option<String>| match part {
+            Some(part) => other_part.as_ref() == Some(part),
+            None => true,
+        };
+
+        self.namespace == other.namespace
+            && self.text_group == other.text_group
+            && part_contains(&self.work, &other.work)
+            && part_contains(&self.version, &other.version)
+            && part_contains(&self.exemplar, &other.exemplar)
+            && match (&self.passage, &other.passage) {
+                (None, _) => true,
+                (Some(_), None) => false,
+                (Some(passage), Some(other_passage)) => passage.contains_passage(other_passage),
+            }
+    }
+}
+
+fn is_component(part: &str) -> bool {
+    !part.is_empty() && part.chars().all(char::is_alphanumeric)
+}
+
+fn is_identifier(part: &str) -> bool {
+    !part.is_empty()
+        && part
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+impl FromStr for Subreference {
+    type Err = UrnError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let invalid = || UrnError::InvalidPassage(text.to_string());
+
+        let (text, index) = match text.strip_suffix(']') {
+            Some(rest) => {
+                let (text, index) = rest.split_once('[').ok_or_else(invalid)?;
+                (
+                    text,
+                    index.parse().ok().filter(|i| *i > 0).ok_or_else(invalid)?,
+                )
+            }
+            None => (text, 1),
+        };
+
+        if text.is_empty() {
+            return Err(invalid());
+        }
+
+        Ok(Self {
+            text: text.to_string(),
+            index,
+        })
+    }
+}
+
+impl FromStr for Reference {
+    type Err = UrnError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let (components, subreference) = match text.split_once('@') {
+            Some((components, subreference)) => (components, Some(subreference.parse()?)),
+            None => (text, None),
+        };
+
+        let components: Vec<_> = components.split('.').map(String::from).collect();
+        if !components.iter().all(|component| is_component(component)) {
+            return Err(UrnError::InvalidPassage(text.to_string()));
+        }
+
+        Ok(Self {
+            components,
+            subreference,
+        })
+    }
+}
+
+impl FromStr for Passage {
+    type Err = UrnError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let invalid = |_| UrnError::InvalidPassage(text.to_string());
+
+        Ok(match text.split_once('-') {
+            Some((start, end)) => Self {
+                start: start.parse().map_err(invalid)?,
+                end: Some(end.parse().map_err(invalid)?),
+            },
+            None => Self {
+                start: text.parse().map_err(invalid)?,
+                end: None,
+            },
+        })
+    }
+}
+
+impl FromStr for CtsUrn {
+    type Err = UrnError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let rest = text
+            .strip_prefix("urn:cts:")
+            .ok_or(UrnError::MissingPrefix)?;
+        let mut parts = rest.splitn(3, ':');
+
+        let namespace = parts.next().filter(|namespace| is_identifier(namespace));
+        let namespace = namespace.ok_or(UrnError::MissingNamespace)?.to_string();
+
+        let work_part = parts
+            .next()
+            .filter(|part| !part.is_empty())
+            .ok_or(UrnError::MissingTextGroup)?;
+        let mut work_components = work_part.split('.').map(String::from);
+        let text_group = work_components.next().unwrap_or_default();
+        let (work, version, exemplar) = (
+            work_components.next(),
+            work_components.next(),
+            work_components.next(),
+        );
+        if work_components.next().is_some()
+            || !is_identifier(&text_group)
+            || ![&work, &version, &exemplar]
+                .iter()
+                .all(|part| part.as_deref().map(is_identifier).unwrap_or(true))
+        {
+            return Err(UrnError::InvalidWork(work_part.to_string()));
+        }
+
+        let passage = match parts.next() {
+            Some(passage) if passage.contains(':') => return Err(UrnError::TooManyComponents),
+            Some(passage) => Some(passage.parse()?),
+            None => None,
+        };
+
+        Ok(Self {
+            namespace,
+            text_group,
+            work,
+            version,
+            exemplar,
+            passage,
+        })
+    }
+}
+
+impl fmt::Display for Reference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.components.join("."))?;
+        match &self.subreference {
+            Some(Subreference { text, index: 1 }) => write!(f, "@{text}"),
+            Some(Subreference { text, index }) => write!(f, "@{text}[{index}]"),
+            None => Ok(()),
+        }
+    }
+}
+
+impl fmt::Display for Passage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.end {
+            Some(end) => write!(f, "{}-{end}", self.start),
+            None => write!(f, "{}", self.start),
+        }
+    }
+}
+
+impl fmt::Display for CtsUrn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "urn:cts:{}:{}", self.namespace, self.text_group)?;
+        for part in [&self.work, &self.version, &self.exemplar]
+            .into_iter()
+            .flatten()
+        {
+            write!(f, ".{part}")?;
+        }
+        if let Some(passage) = &self.passage {
+            write!(f, ":{passage}")?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,117 @@
+use crate::formatters::Language;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Words printed around the texts themselves: headings, page headers and colophons.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Word {
+    /// Heading of a book of a work, followed by its number
+    Book,
+    /// Heading of a chapter, followed by its number, none by default
+    Chapter,
+    /// Heading of a letter, followed by its number
+    Letter,
+    /// Title page of a volume of a series, followed by its number
+    Volume,
+    /// Heading of the list of characters of a play
+    CastList,
+    /// Table of contents
+    Contents,
+    /// Index of the works of all volumes of a series
+    SeriesContents,
+    EditorsNote,
+    Editors,
+    Source,
+    Licence,
+    /// Colophon after the last work
+    TheEnd,
+    /// Colophon after the table of contents
+    EndOfContents,
+    /// Footnote marking text lost from the manuscripts
+    Lacuna,
+}
+
+impl Word {
+    /// Furniture of the books of the series, Latin apart from some headings of Greek books.
+    pub fn default_for(self, language: &Language) -> &'static str {
+        match (self, language) {
+            (Self::Book, Language::Latin) => "Liber",
+            (Self::Book, Language::Greek) => "Βιβλίον",
+            (Self::Chapter, _) => "",
+            (Self::Letter, Language::Latin) => "Epistola",
+            (Self::Letter, Language::Greek) => "Ἐπιστολή",
+            (Self::Volume, Language::Latin) => "Tomus",
+            (Self::Volume, Language::Greek) => "Τόμος",
+            (Self::CastList, Language::Latin) => "Personae",
+            (Self::CastList, Language::Greek) => "Τὰ τοῦ δράματος πρόσωπα",
+            (Self::Contents, _) => "Index",
+            (Self::SeriesContents, _) => "Index operum",
+            (Self::EditorsNote, _) => "Nota editoris",
+            (Self::Editors, _) => "Editores",
+            (Self::Source, _) => "Fons",
+            (Self::Licence, _) => "Licentia",
+            (Self::TheEnd, _) => "FINIS",
+            (Self::EndOfContents, _) => "FINIS TABULÆ",
+            // "lost" does not look good when all other footnotes are in Latin
+            (Self::Lacuna, _) => "lacuna",
+        }
+    }
+}
+
+/// Words replacing the defaults of the language of the book, e.g. `{ "book": "Book" }`.
+#[derive(Default, Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct Vocabulary {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub book: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chapter: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub letter: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub volume: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cast_list: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub contents: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub series_contents: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub editors_note: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub editors: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub licence: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub the_end: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end_of_contents: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lacuna: Option<String>,
+}
+
+impl Vocabulary {
+    pub fn word(&self, word: Word, language: &Language) -> &str {
+        let custom = match word {
+            Word::Book => &self.book,
+            Word::Chapter => &self.chapter,
+            Word::Letter => &self.letter,
+            Word::Volume => &self.volume,
+            Word::CastList => &self.cast_list,
+            Word::Contents => &self.contents,
+            Word::SeriesContents => &self.series_contents,
+            Word::EditorsNote => &self.editors_note,
+            Word::Editors => &self.editors,
+            Word::Source => &self.source,
+            Word::Licence => &self.licence,
+            Word::TheEnd => &self.the_end,
+            Word::EndOfContents => &self.end_of_contents,
+            Word::Lacuna => &self.lacuna,
+        };
+
+        custom
+            .as_deref()
+            .unwrap_or_else(|| word.default_for(language))
+    }
+}
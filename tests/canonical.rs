@@ -0,0 +1,119 @@
+mod common;
+
+use common::fixture_path;
+use egrapsa::text::TextNode;
+use egrapsa::text_sources::canonical::Canonical;
+use egrapsa::text_sources::{GetTextError, TextSource};
+
+const ILIAD: &str = "urn:cts:greekLit:tlg0012.tlg001.perseus-grc2";
+
+fn repository() -> Canonical {
+    Canonical::new(fixture_path("canonical"))
+}
+
+fn text(id: &str) -> String {
+    TextNode::to_string(&repository().get_text(id).unwrap())
+}
+
+#[test]
+fn editions_are_found_through_cts_files() {
+    let path = repository().edition_path(&ILIAD.parse().unwrap()).unwrap();
+
+    assert_eq!(
+        path,
+        fixture_path("canonical/data/tlg0012/tlg001/tlg0012.tlg001.perseus-grc2.xml")
+    );
+}
+
+#[test]
+fn works_resolve_to_first_edition_in_original_language() {
+    let path = repository()
+        .edition_path(&"urn:cts:greekLit:tlg0062.tlg017".parse().unwrap())
+        .unwrap();
+
+    assert!(path.ends_with("tlg0062.tlg017.perseus-grc2.xml"));
+}
+
+#[test]
+fn data_directory_can_be_given_directly() {
+    let repository = Canonical::new(fixture_path("canonical/data"));
+
+    assert!(repository.get_text(ILIAD).is_ok());
+}
+
+#[test]
+fn whole_edition_keeps_header() {
+    let (text, metadata) = repository().get_text_with_metadata(ILIAD).unwrap();
+    let text = TextNode::to_string(&text);
+    let metadata = metadata.unwrap();
+
+    assert!(text.contains("μῆνιν ἄειδε"));
+    assert!(text.contains("κόσμηθεν"));
+    assert_eq!(metadata.title.as_deref(), Some("Ἰλιάς"));
+    assert_eq!(metadata.author.as_deref(), Some("Homer"));
+}
+
+#[test]
+fn ranges_across_books_are_cut_locally() {
+    let text = text(&format!("{ILIAD}:1.3-2.1"));
+
+    assert!(!text.contains("μῆνιν"));
+    assert!(!text.contains("οὐλομένην"));
+    assert!(text.contains("ψυχὰς"));
+    assert!(text.contains("ἡρώων"));
+    assert!(text.contains("ἱπποκορυσταὶ"));
+    assert!(!text.contains("εὗδον"));
+    assert!(!text.contains("κόσμηθεν"));
+}
+
+#[test]
+fn whole_units_are_selected() {
+    let text = text(&format!("{ILIAD}:2"));
+
+    assert!(text.contains("ἱπποκορυσταὶ"));
+    assert!(text.contains("εὗδον"));
+    assert!(!text.contains("ἡρώων"));
+    assert!(!text.contains("κόσμηθεν"));
+
+    let text = self::text("urn:cts:greekLit:tlg0062.tlg017.perseus-grc2:2");
+    assert!(text.contains("ἐρισμάραγος"));
+    assert!(!text.contains("φίλιε"));
+}
+
+#[test]
+fn ranges_within_speeches_and_stanzas() {
+    const PROMETHEUS: &str = "urn:cts:greekLit:tlg0085.tlg003.perseus-grc2";
+
+    let text = text(&format!("{PROMETHEUS}:4-12"));
+    assert!(!text.contains("Χθονὸς"));
+    assert!(text.contains("ΚΡΑΤΟΣ"));
+    assert!(text.contains("ἐφεῖτο"));
+    assert!(text.contains("ὀχμάσαι"));
+    assert!(text.contains("ΗΦΑΙΣΤΟΣ"));
+    assert!(text.contains("ἐντολὴ Διὸς"));
+    assert!(!text.contains("ἐμποδὼν"));
+    assert!(!text.contains("ΧΟΡΟΣ"));
+
+    let text = self::text(&format!("{PROMETHEUS}:128-129"));
+    assert!(text.contains("ΧΟΡΟΣ"));
+    assert!(text.contains("φιλία"));
+    assert!(text.contains("ἁμίλλαις"));
+    assert!(!text.contains("πάγον"));
+    assert!(!text.contains("ΚΡΑΤΟΣ"));
+}
+
+#[test]
+fn missing_works_and_passages_are_errors() {
+    assert!(matches!(
+        repository().get_text("urn:cts:greekLit:tlg0012.tlg002.perseus-grc2"),
+        Err(GetTextError::NotInCatalog(urn)) if urn == "urn:cts:greekLit:tlg0012.tlg002.perseus-grc2"
+    ));
+    assert!(matches!(
+        repository().get_text("urn:cts:greekLit:tlg0062.tlg017.perseus-grc5:1"),
+        Err(GetTextError::NotInCatalog(_))
+    ));
+    assert!(matches!(
+        repository().get_text(&format!("{ILIAD}:4.1")),
+        Err(GetTextError::PassageNotFound(passage)) if passage == "4.1"
+    ));
+}
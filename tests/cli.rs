@@ -0,0 +1,197 @@
+mod common;
+
+use common::fixture;
+use egrapsa::text_sources::cache::Cache;
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+const PHALARIS: &str = "urn:cts:greekLit:tlg0062.tlg001.perseus-grc2:1.1-2.1";
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("egrapsa-cli-{}-{name}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn egrapsa(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_egrapsa"))
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).to_string()
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stderr).to_string()
+}
+
+fn write_config(dir: &std::path::Path, identifier: &str) -> PathBuf {
+    let path = dir.join("config.json");
+    let config = format!(
+        r#"{{
+    "name": "Phalaris",
+    "formatter_type": "Latex",
+    "formatter_config": {{
+        "title": "Phalaris",
+        "author": "Lucianus Samosatensis",
+        "catchwords": false,
+        "ref_numbers": true,
+        "footnotes": false,
+        "language": "Greek"
+    }},
+    "source_type": "Scaife",
+    "work_infos": [
+        {{
+            "title": "Φάλαρις",
+            "alt_title": "Phalaris",
+            "identifier": "{identifier}"
+        }}
+    ]
+}}"#
+    );
+    std::fs::write(&path, config).unwrap();
+    path
+}
+
+// Fills the cache, so that no network access is needed
+fn cache_dir(dir: &std::path::Path) -> PathBuf {
+    let cache_dir = dir.join("cache");
+    Cache::new(cache_dir.clone())
+        .put("scaife", PHALARIS, &fixture("scaife/lucian_phalaris.xml"))
+        .unwrap();
+    cache_dir
+}
+
+#[test]
+fn list_sources_and_formatters() {
+    assert!(stdout(&egrapsa(&["list-sources"])).starts_with("Scaife\t"));
+    assert!(stdout(&egrapsa(&["list-formatters"])).starts_with("Latex\t"));
+}
+
+#[test]
+fn validate_accepts_shipped_configs() {
+    let configs = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("configs");
+    for entry in std::fs::read_dir(configs).unwrap() {
+        let path = entry.unwrap().path();
+        // Base configs are fragments extended by the others
+        if path.is_dir() {
+            continue;
+        }
+
+        let output = egrapsa(&["validate", "-c", path.to_str().unwrap()]);
+        assert!(output.status.success(), "{}", stderr(&output));
+    }
+}
+
+#[test]
+fn validate_reports_bad_identifiers() {
+    let dir = temp_dir("validate");
+    let config = write_config(&dir, "tlg0062.tlg001");
+
+    let output = egrapsa(&["validate", "-c", config.to_str().unwrap()]);
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("unknown identifier prefix in `tlg0062.tlg001`"));
+}
+
+#[test]
+fn build_from_cache() {
+    let dir = temp_dir("build");
+    let config = write_config(&dir, PHALARIS);
+    let cache_dir = cache_dir(&dir);
+    let output_path = dir.join("phalaris.tex");
+
+    let output = egrapsa(&[
+        "build",
+        "--cache-dir",
+        cache_dir.to_str().unwrap(),
+        "-c",
+        config.to_str().unwrap(),
+        "-o",
+        output_path.to_str().unwrap(),
+    ]);
+    assert!(output.status.success(), "{}", stderr(&output));
+
+    let tex = std::fs::read_to_string(output_path).unwrap();
+    assert!(tex.contains(r"\chapter*{Φάλαρις.}"));
+    assert!(tex.contains("Ἔπεμψεν ἡμᾶς"));
+}
+
+#[test]
+fn inspect_from_cache() {
+    let dir = temp_dir("inspect");
+    let cache_dir = cache_dir(&dir);
+
+    let output = egrapsa(&[
+        "inspect",
+        "--cache-dir",
+        cache_dir.to_str().unwrap(),
+        PHALARIS,
+    ]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout(&output).contains("kind: Book"));
+    assert!(stdout(&output).contains("Φάλαρις πρῶτος"));
+}
+
+#[test]
+fn build_series_from_cache() {
+    const GALLUS: &str = "urn:cts:greekLit:tlg0062.tlg019.perseus-grc2:1-2";
+
+    let dir = temp_dir("series");
+    let cache_dir = cache_dir(&dir);
+    Cache::new(cache_dir.clone())
+        .put("scaife", GALLUS, &fixture("scaife/lucian_gallus.xml"))
+        .unwrap();
+
+    let config = dir.join("series.toml");
+    std::fs::write(
+        &config,
+        format!(
+            r#"
+name = "Lucian"
+formatter_type = "Latex"
+source_type = "Scaife"
+volume_budget = {{ words = 100 }}
+
+[formatter_config]
+title = "Opera Omnia"
+catchwords = false
+ref_numbers = false
+footnotes = false
+language = "Greek"
+
+[[work_infos]]
+title = "Φάλαρις"
+identifier = "{PHALARIS}"
+
+[[work_infos]]
+title = "Ὄνειρος ἢ Ἀλεκτρυών"
+identifier = "{GALLUS}"
+"#
+        ),
+    )
+    .unwrap();
+
+    let output = egrapsa(&[
+        "build",
+        "--cache-dir",
+        cache_dir.to_str().unwrap(),
+        "-c",
+        config.to_str().unwrap(),
+        "-o",
+        dir.join("lucian.tex").to_str().unwrap(),
+    ]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout(&output).contains("Splitting into 2 volumes."));
+
+    let first = std::fs::read_to_string(dir.join("lucian-1.tex")).unwrap();
+    let second = std::fs::read_to_string(dir.join("lucian-2.tex")).unwrap();
+    assert!(first.contains(r"\large Τόμος I}"));
+    assert!(!first.contains("Index operum"));
+    assert!(second.contains(r"\large Τόμος II}"));
+    assert!(second.contains("Index operum"));
+    assert!(dir.join("lucian-spines.tex").is_file());
+}
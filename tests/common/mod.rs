@@ -0,0 +1,158 @@
+// Not every test binary uses every helper
+#![allow(dead_code)]
+
+pub mod snapshot;
+
+use egrapsa::config::FormatterConfig;
+use egrapsa::formatters::{latex::Latex, TextFormatter, Work};
+use egrapsa::metadata::Metadata;
+use egrapsa::text::TextParent;
+use egrapsa::text_sources::read_tei_file;
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::PathBuf;
+
+pub fn fixture_path(path: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join(path)
+}
+
+pub fn fixture(path: &str) -> String {
+    std::fs::read_to_string(fixture_path(path))
+        .unwrap_or_else(|e| panic!("Could not read fixture {path}: {e}"))
+}
+
+/// Text of a TEI file in `fixtures/tei`, e.g. `tei_fixture("ovid_amores.xml")`.
+pub fn tei_fixture(name: &str) -> TextParent {
+    let path = fixture_path(&format!("tei/{name}"));
+    read_tei_file(&path.to_string_lossy()).unwrap().0
+}
+
+/// LaTeX book of a single work, the text of a TEI file in `fixtures/tei`.
+pub fn document(config: FormatterConfig, title: &str, fixture: &str) -> String {
+    let mut formatter = Latex::new();
+    config.apply(&mut formatter);
+    formatter.add_work(Work {
+        title: String::from(title),
+        alt_title: None,
+        author: None,
+        metadata: Metadata::default(),
+        text: tei_fixture(fixture),
+    });
+
+    formatter.format().unwrap()
+}
+
+/// Local stand-in for the Scaife library, answering `/library/{urn}/cts-api-xml`
+/// with recorded CTS `GetPassage` replies.
+pub struct MockScaife {
+    library_url: String,
+}
+
+impl MockScaife {
+    pub fn serve(passages: &[(&str, String)]) -> Self {
+        let base_url = serve(
+            passages
+                .iter()
+                .map(|(urn, body)| (format!("/library/{urn}/cts-api-xml"), body.clone()))
+                .collect(),
+        );
+
+        Self {
+            library_url: format!("{base_url}/library"),
+        }
+    }
+
+    pub fn library_url(&self) -> &str {
+        &self.library_url
+    }
+}
+
+/// Local stand-in for a CTS API, answering `/api/cts?{query}` with recorded replies.
+pub struct MockCts {
+    api_url: String,
+}
+
+impl MockCts {
+    pub fn serve(replies: &[(&str, String)]) -> Self {
+        let base_url = serve(
+            replies
+                .iter()
+                .map(|(query, body)| (format!("/api/cts?{query}"), body.clone()))
+                .collect(),
+        );
+
+        Self {
+            api_url: format!("{base_url}/api/cts"),
+        }
+    }
+
+    pub fn api_url(&self) -> &str {
+        &self.api_url
+    }
+}
+
+/// Local server answering requests whose path and query match one of the routes exactly.
+pub struct MockServer {
+    base_url: String,
+}
+
+impl MockServer {
+    pub fn serve(routes: &[(&str, String)]) -> Self {
+        Self {
+            base_url: serve(
+                routes
+                    .iter()
+                    .map(|(path, body)| (path.to_string(), body.clone()))
+                    .collect(),
+            ),
+        }
+    }
+
+    pub fn url(&self, path: &str) -> String {
+        format!("{}{path}", self.base_url)
+    }
+}
+
+// Serves the given bodies by path on a random port and returns the base URL
+fn serve(routes: HashMap<String, String>) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+
+    std::thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            respond(stream, &routes);
+        }
+    });
+
+    base_url
+}
+
+fn respond(mut stream: TcpStream, routes: &HashMap<String, String>) {
+    let mut reader = BufReader::new(stream.try_clone().unwrap());
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).unwrap();
+
+    // Headers are irrelevant, but have to be consumed before replying
+    let mut header = String::new();
+    while reader.read_line(&mut header).unwrap() > 2 {
+        header.clear();
+    }
+
+    let path = request_line.split_whitespace().nth(1);
+
+    let (status, body) = match path.and_then(|path| routes.get(path)) {
+        Some(body) => ("200 OK", body.as_str()),
+        None => ("404 Not Found", ""),
+    };
+
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\nContent-Type: text/xml; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )
+    .unwrap();
+}
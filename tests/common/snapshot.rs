@@ -0,0 +1,47 @@
+use std::path::PathBuf;
+
+/// Set to regenerate golden files instead of comparing against them, e.g.
+/// `EGRAPSA_UPDATE_SNAPSHOTS=1 cargo test --test latex`.
+pub const UPDATE_VAR: &str = "EGRAPSA_UPDATE_SNAPSHOTS";
+
+fn snapshot_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("snapshots")
+        .join(name)
+}
+
+pub fn assert_snapshot(name: &str, actual: &str) {
+    let path = snapshot_path(name);
+
+    if std::env::var_os(UPDATE_VAR).is_some() {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, actual).unwrap();
+        return;
+    }
+
+    let expected = std::fs::read_to_string(&path).unwrap_or_else(|_| {
+        panic!("Missing snapshot {name}, run the test with {UPDATE_VAR}=1 to create it")
+    });
+
+    if expected == actual {
+        return;
+    }
+
+    // Texts differing only in line endings have the same lines
+    let line_count = expected.lines().count().max(actual.lines().count()) + 1;
+    let (line, (expected_line, actual_line)) = expected
+        .split('\n')
+        .chain(std::iter::repeat("<EOF>"))
+        .zip(actual.split('\n').chain(std::iter::repeat("<EOF>")))
+        .take(line_count)
+        .enumerate()
+        .find(|(_, (e, a))| e != a)
+        .unwrap();
+
+    panic!(
+        "Snapshot {name} differs at line {}:\n  expected: {expected_line}\n  actual:   {actual_line}\n\
+         Run the test with {UPDATE_VAR}=1 if the change is intended",
+        line + 1
+    );
+}
@@ -0,0 +1,343 @@
+mod common;
+
+use egrapsa::config::validation::{validate, Diagnostic, Severity};
+use egrapsa::config::{Config, ConfigError};
+use serde_json::{json, Value};
+
+fn config(work_infos: Value) -> Value {
+    json!({
+        "name": "Lucian's complete works",
+        "formatter_type": "Latex",
+        "formatter_config": {
+            "title": "Opera Omnia",
+            "author": "Lucianus Samosatensis",
+            "catchwords": false,
+            "ref_numbers": true,
+            "footnotes": true,
+            "language": "Greek"
+        },
+        "source_type": "Scaife",
+        "work_infos": work_infos
+    })
+}
+
+fn phalaris() -> Value {
+    json!({
+        "title": "Φάλαρις",
+        "alt_title": "Phalaris",
+        "identifier": "urn:cts:greekLit:tlg0062.tlg001.perseus-grc2:1.1-2.13"
+    })
+}
+
+fn error(path: &str, message: &str) -> Diagnostic {
+    Diagnostic {
+        severity: Severity::Error,
+        path: String::from(path),
+        message: String::from(message),
+    }
+}
+
+#[test]
+fn valid_config() {
+    assert_eq!(validate(&config(json!([phalaris()]))), vec![]);
+}
+
+#[test]
+fn unknown_keys_with_suggestions() {
+    let mut value = config(json!([phalaris()]));
+    value["formatter_config"]["refnumbers"] = json!(true);
+    value["formatter_config"]["xyz"] = json!(true);
+    value["work_infos"][0]["alt_tilte"] = json!("Phalaris");
+
+    assert_eq!(
+        validate(&value),
+        vec![
+            error(
+                "$.formatter_config.refnumbers",
+                "unknown key `refnumbers`, did you mean `ref_numbers`?"
+            ),
+            error("$.formatter_config.xyz", "unknown key `xyz`"),
+            error(
+                "$.work_infos[0].alt_tilte",
+                "unknown key `alt_tilte`, did you mean `alt_title`?"
+            ),
+        ]
+    );
+}
+
+#[test]
+fn numeral_styles_are_checked() {
+    let mut value = config(json!([phalaris()]));
+    value["formatter_config"]["numerals"] = json!({ "books": "Greek", "folio": "Roman" });
+    assert_eq!(
+        validate(&value),
+        vec![error(
+            "$.formatter_config.numerals.folio",
+            "unknown key `folio`, did you mean `folios`?"
+        )]
+    );
+
+    value["formatter_config"]["numerals"] = json!({ "books": "Hebrew" });
+    let diagnostics = validate(&value);
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].path, "$.formatter_config.numerals.books");
+}
+
+#[test]
+fn type_errors_are_located() {
+    let mut value = config(json!([phalaris(), phalaris()]));
+    value["work_infos"][1]["identifier"] = json!(12);
+
+    let diagnostics = validate(&value);
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].path, "$.work_infos[1].identifier");
+    assert!(diagnostics[0]
+        .message
+        .starts_with("invalid type: integer `12`"));
+}
+
+#[test]
+fn missing_keys_are_reported() {
+    let mut value = config(json!([phalaris()]));
+    value["formatter_config"]
+        .as_object_mut()
+        .unwrap()
+        .remove("footnotes");
+
+    assert_eq!(
+        validate(&value),
+        vec![error("$.formatter_config", "missing field `footnotes`")]
+    );
+}
+
+#[test]
+fn duplicate_titles_are_warnings() {
+    let diagnostics = validate(&config(json!([phalaris(), phalaris()])));
+
+    assert_eq!(
+        diagnostics,
+        vec![Diagnostic {
+            severity: Severity::Warning,
+            path: String::from("$.work_infos[1].title"),
+            message: String::from(
+                "duplicate work title `Φάλαρις`, first used at $.work_infos[0].title"
+            ),
+        }]
+    );
+}
+
+#[test]
+fn missing_titles_are_warnings() {
+    let mut untitled = phalaris();
+    untitled["title"] = Value::Null;
+    let timon = json!({
+        "identifier": format!("file:{}", common::fixture_path("tei/lucian_timon.xml").display())
+    });
+
+    assert_eq!(
+        validate(&config(json!([untitled, timon]))),
+        vec![Diagnostic {
+            severity: Severity::Warning,
+            path: String::from("$.work_infos[0].title"),
+            message: String::from(
+                "no title is given for `urn:cts:greekLit:tlg0062.tlg001.perseus-grc2:1.1-2.13`, \
+                 which is printed instead unless the source has one"
+            ),
+        }]
+    );
+}
+
+#[test]
+fn identifiers_are_checked() {
+    let value = config(json!([
+        { "title": "A", "alt_title": null, "identifier": "tlg0062.tlg001" },
+        { "title": "B", "alt_title": null, "identifier": "urn:cts:greekLit" },
+        { "title": "C", "alt_title": null, "identifier": "file:/nonexistent/phalaris.xml" },
+        { "title": "D", "alt_title": null, "identifier": "urn:cts:latinLit:phi0474.phi001.perseus-lat2:1-99" },
+        { "title": "E", "alt_title": null, "identifier": "urn:cts:greekLit:tlg0012.tlg001" },
+        { "title": "F", "alt_title": null, "identifier": "urn:cts:greekLit:tlg0012.tlg001.perseus-grc2:2.1-1.5" },
+    ]));
+
+    assert_eq!(
+        validate(&value),
+        vec![
+            error(
+                "$.work_infos[0].identifier",
+                "unknown identifier prefix in `tlg0062.tlg001`, expected `urn:`, `file:` or `dts:`"
+            ),
+            error(
+                "$.work_infos[1].identifier",
+                "`urn:cts:greekLit` is not a well formed CTS URN: the text group is missing"
+            ),
+            error(
+                "$.work_infos[2].identifier",
+                "file `/nonexistent/phalaris.xml` does not exist"
+            ),
+            error(
+                "$.work_infos[5].identifier",
+                "the passage of `urn:cts:greekLit:tlg0012.tlg001.perseus-grc2:2.1-1.5` ends before it starts"
+            ),
+        ]
+    );
+}
+
+#[test]
+fn dts_identifiers_need_dts_source() {
+    let mut value = config(json!([
+        { "title": "A", "alt_title": null, "identifier": "dts:lucian-timon" },
+        { "title": "B", "alt_title": null, "identifier": "dts:" },
+    ]));
+
+    assert_eq!(
+        validate(&value),
+        vec![
+            error(
+                "$.work_infos[0].identifier",
+                "`dts:lucian-timon` can only be used with the `Dts` source"
+            ),
+            error(
+                "$.work_infos[1].identifier",
+                "`dts:` can only be used with the `Dts` source"
+            ),
+        ]
+    );
+
+    value["source_type"] = json!("Dts");
+    assert_eq!(
+        validate(&value),
+        vec![error(
+            "$.work_infos[1].identifier",
+            "the DTS identifier is empty"
+        )]
+    );
+}
+
+#[test]
+fn canonical_source_needs_repository() {
+    let mut value = config(json!([phalaris()]));
+    value["source_type"] = json!("Canonical");
+
+    assert_eq!(
+        validate(&value),
+        vec![error(
+            "$.source_url",
+            "the `Canonical` source needs the path of a local repository"
+        )]
+    );
+
+    value["source_url"] = json!("/nonexistent/canonical-greekLit");
+    assert_eq!(
+        validate(&value),
+        vec![error(
+            "$.source_url",
+            "directory `/nonexistent/canonical-greekLit` does not exist"
+        )]
+    );
+
+    value["source_url"] = json!(common::fixture_path("canonical"));
+    assert!(validate(&value).is_empty());
+}
+
+#[test]
+fn formats_are_picked_by_extension() {
+    let fixtures = common::fixture_path("config");
+    let load = |name: &str| {
+        serde_json::to_value(Config::from_path(&fixtures.join(name)).unwrap()).unwrap()
+    };
+
+    let json = load("phalaris.json");
+    assert_eq!(load("phalaris.toml"), json);
+    assert_eq!(load("phalaris.yaml"), json);
+}
+
+#[test]
+fn schema_describes_config() {
+    let schema: Value = serde_json::from_str(&Config::json_schema()).unwrap();
+
+    assert_eq!(schema["title"], "Config");
+    assert!(schema["$defs"]["FormatterConfig"]["properties"]["ref_numbers"].is_object());
+    assert!(schema["$defs"]["WorkInfo"]["required"]
+        .as_array()
+        .unwrap()
+        .contains(&json!("identifier")));
+}
+
+#[test]
+fn schema_key_is_allowed() {
+    let mut value = config(json!([phalaris()]));
+    value["$schema"] = json!("./config.schema.json");
+
+    assert_eq!(validate(&value), vec![]);
+}
+
+#[test]
+fn extends_and_includes_are_merged() {
+    let path = common::fixture_path("config/series_volume.json");
+    let value = serde_json::to_value(Config::from_path(&path).unwrap()).unwrap();
+
+    assert_eq!(value["name"], "Lucian, volume I");
+    assert_eq!(value["formatter_config"]["author"], "Lucianus Samosatensis");
+    assert_eq!(value["formatter_config"]["catchwords"], true);
+    assert_eq!(value["formatter_config"]["footnotes"], true);
+    assert!(value.get("extends").is_none());
+    assert!(value.get("includes").is_none());
+
+    let titles: Vec<_> = value["work_infos"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|work_info| work_info["alt_title"].as_str().unwrap())
+        .collect();
+    assert_eq!(titles, ["Phalaris", "Hippias", "Electrum"]);
+}
+
+#[test]
+fn paths_are_relative_to_their_config() {
+    let path = common::fixture_path("config/paths_volume.json");
+    assert!(Config::diagnostics(&path).unwrap().is_empty());
+
+    let value = serde_json::to_value(Config::from_path(&path).unwrap()).unwrap();
+    let resolved = |value: &Value| {
+        std::path::Path::new(value.as_str().unwrap())
+            .canonicalize()
+            .unwrap()
+    };
+    assert_eq!(
+        resolved(&value["formatter_config"]["templates"]),
+        common::fixture_path("templates/press")
+            .canonicalize()
+            .unwrap()
+    );
+    assert_eq!(
+        resolved(&value["source_url"]),
+        common::fixture_path("canonical").canonicalize().unwrap()
+    );
+    assert!(std::path::Path::new(value["catalog"].as_str().unwrap()).is_file());
+}
+
+#[test]
+fn cyclic_inheritance_is_an_error() {
+    let path = common::fixture_path("config/cyclic_a.json");
+
+    assert!(matches!(
+        Config::from_path(&path),
+        Err(ConfigError::CyclicInheritance(_))
+    ));
+}
+
+#[test]
+fn shipped_volumes_share_a_base() {
+    let configs = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("configs");
+    let volume =
+        |name: &str| serde_json::to_value(Config::from_path(&configs.join(name)).unwrap()).unwrap();
+
+    let lucian = volume("lucian-4.json");
+    let pseudolucian = volume("lucian-6.json");
+    assert_eq!(lucian["formatter_config"]["title"], "Opera Omnia");
+    assert_eq!(pseudolucian["formatter_config"]["title"], "Opera Omnia");
+    assert_eq!(
+        pseudolucian["formatter_config"]["author"],
+        "Pseudolucianus Samosatensis"
+    );
+    assert_eq!(pseudolucian["name"], "Pseudolucian's complete works");
+}
@@ -0,0 +1,229 @@
+mod common;
+
+use common::{fixture, fixture_path, MockCts};
+use egrapsa::config::validation::validate;
+use egrapsa::config::Config;
+use egrapsa::text_sources::cts::{Catalog, CtsApi};
+use egrapsa::text_sources::GetTextError;
+use serde_json::{json, Value};
+
+const PHALARIS: &str = "urn:cts:greekLit:tlg0062.tlg001.perseus-grc2";
+const GALLUS: &str = "urn:cts:greekLit:tlg0062.tlg019.perseus-grc2";
+const ILIAD: &str = "urn:cts:greekLit:tlg0012.tlg001.perseus-grc2";
+
+fn catalog() -> Catalog {
+    Catalog::parse(&fixture("cts/capabilities.xml")).unwrap()
+}
+
+fn reff(urn: &str, level: usize, fixture_name: &str) -> (String, String) {
+    (
+        format!("request=GetValidReff&urn={urn}&level={level}"),
+        fixture(&format!("cts/{fixture_name}")),
+    )
+}
+
+// Without `GetCapabilities`, so that only local catalogs can be used
+fn serve_reffs() -> MockCts {
+    let replies = [
+        reff(PHALARIS, 2, "phalaris_reff.xml"),
+        reff(GALLUS, 1, "gallus_reff.xml"),
+        reff(ILIAD, 2, "iliad_reff.xml"),
+    ];
+    let replies: Vec<_> = replies
+        .iter()
+        .map(|(query, body)| (query.as_str(), body.clone()))
+        .collect();
+
+    MockCts::serve(&replies)
+}
+
+fn config(extra: Value) -> Value {
+    let mut value = json!({
+        "name": "Lucian",
+        "formatter_type": "Latex",
+        "formatter_config": {
+            "catchwords": false,
+            "ref_numbers": true,
+            "footnotes": true,
+            "language": "Greek"
+        },
+        "source_type": "Scaife"
+    });
+    value
+        .as_object_mut()
+        .unwrap()
+        .extend(extra.as_object().unwrap().clone());
+    value
+}
+
+fn resolve(extra: Value, cts: &CtsApi) -> Result<Vec<(Option<String>, String)>, GetTextError> {
+    let config: Config = serde_json::from_value(config(extra)).unwrap();
+
+    Ok(config
+        .resolve_work_infos(cts)?
+        .into_iter()
+        .map(|work_info| (work_info.title, work_info.identifier))
+        .collect())
+}
+
+#[test]
+fn catalog_is_parsed() {
+    let catalog = catalog();
+
+    assert_eq!(catalog.text_groups.len(), 2);
+    assert_eq!(catalog.text_groups[0].name.as_deref(), Some("Lucian"));
+
+    let phalaris = &catalog.text_groups[0].works[0];
+    assert_eq!(phalaris.title.as_deref(), Some("Phalaris"));
+    assert_eq!(phalaris.editions.len(), 2);
+    assert_eq!(phalaris.editions[0].citation_depth, 2);
+    assert!(!phalaris.editions[0].translation);
+    assert_eq!(phalaris.editions[1].version(), "perseus-eng2");
+    assert!(phalaris.editions[1].translation);
+
+    assert_eq!(catalog.edition(GALLUS).unwrap().citation_depth, 1);
+}
+
+#[test]
+fn text_group_selects_original_editions() {
+    let catalog = catalog();
+    let editions: Vec<_> = catalog
+        .select("urn:cts:greekLit:tlg0062", None)
+        .unwrap()
+        .into_iter()
+        .map(|(_, edition)| edition.urn.as_str())
+        .collect();
+
+    assert_eq!(editions, vec![PHALARIS, GALLUS]);
+}
+
+#[test]
+fn edition_prefix_filters_editions() {
+    let catalog = catalog();
+    let editions: Vec<_> = catalog
+        .select("urn:cts:greekLit:tlg0062", Some("perseus-eng"))
+        .unwrap()
+        .into_iter()
+        .map(|(_, edition)| edition.urn.as_str())
+        .collect();
+
+    assert_eq!(
+        editions,
+        vec!["urn:cts:greekLit:tlg0062.tlg001.perseus-eng2"]
+    );
+}
+
+#[test]
+fn unknown_selection_is_an_error() {
+    // `tlg00` must not match `tlg0062` just because it is a prefix
+    assert!(matches!(
+        catalog().select("urn:cts:greekLit:tlg00", None),
+        Err(GetTextError::NotInCatalog(_))
+    ));
+}
+
+#[test]
+fn full_range_from_valid_reff() {
+    let server = serve_reffs();
+    let cts = CtsApi::with_api_url(server.api_url());
+    let catalog = catalog();
+
+    assert_eq!(
+        cts.full_range(catalog.edition(PHALARIS).unwrap())
+            .unwrap()
+            .to_string(),
+        format!("{PHALARIS}:1.1-2.13")
+    );
+    assert_eq!(
+        cts.full_range(catalog.edition(GALLUS).unwrap())
+            .unwrap()
+            .to_string(),
+        format!("{GALLUS}:1-33")
+    );
+}
+
+#[test]
+fn selections_and_whole_editions_are_resolved() {
+    let server = serve_reffs();
+    let cts = CtsApi::with_api_url(server.api_url());
+
+    let works = resolve(
+        json!({
+            "catalog": fixture_path("cts/capabilities.xml"),
+            "work_infos": [{ "title": "Ἰλιάς", "identifier": ILIAD }],
+            "selections": [{ "urn": "urn:cts:greekLit:tlg0062", "edition": "perseus-grc" }]
+        }),
+        &cts,
+    )
+    .unwrap();
+
+    assert_eq!(
+        works,
+        vec![
+            (Some(String::from("Ἰλιάς")), format!("{ILIAD}:1.1-24.804")),
+            (
+                Some(String::from("Phalaris")),
+                format!("{PHALARIS}:1.1-2.13")
+            ),
+            (Some(String::from("Gallus")), format!("{GALLUS}:1-33")),
+        ]
+    );
+}
+
+#[test]
+fn catalog_is_requested_from_the_library() {
+    let capabilities = fixture("cts/capabilities.xml");
+    let (query, reply) = reff(GALLUS, 1, "gallus_reff.xml");
+    let server = MockCts::serve(&[
+        ("request=GetCapabilities", capabilities),
+        (query.as_str(), reply),
+    ]);
+    let cts = CtsApi::with_api_url(server.api_url());
+
+    let works = resolve(
+        json!({ "selections": [{ "urn": "urn:cts:greekLit:tlg0062.tlg019" }] }),
+        &cts,
+    )
+    .unwrap();
+
+    assert_eq!(
+        works,
+        vec![(Some(String::from("Gallus")), format!("{GALLUS}:1-33"))]
+    );
+}
+
+#[test]
+fn listed_passages_need_no_catalog() {
+    // Nothing listens there, any request would fail
+    let cts = CtsApi::with_api_url("http://127.0.0.1:1/api/cts");
+    let identifier = format!("{PHALARIS}:1.1-1.2");
+
+    let works = resolve(
+        json!({ "work_infos": [{ "title": "Φάλαρις", "identifier": identifier }] }),
+        &cts,
+    )
+    .unwrap();
+
+    assert_eq!(works, vec![(Some(String::from("Φάλαρις")), identifier)]);
+}
+
+#[test]
+fn selections_are_validated() {
+    let diagnostics = validate(&config(json!({
+        "catalog": "missing/capabilities.xml",
+        "selections": [{ "urn": "tlg0062", "editon": "perseus-grc" }]
+    })));
+    let messages: Vec<_> = diagnostics
+        .iter()
+        .map(|diagnostic| diagnostic.to_string())
+        .collect();
+
+    assert_eq!(
+        messages,
+        vec![
+            "error: $.selections[0].editon: unknown key `editon`, did you mean `edition`?",
+            "error: $.selections[0].urn: `tlg0062` is not the CTS URN of a text group or a work",
+            "error: $.catalog: file `missing/capabilities.xml` does not exist",
+        ]
+    );
+}
@@ -0,0 +1,63 @@
+mod common;
+
+use common::{document, tei_fixture};
+use egrapsa::config::FormatterConfig;
+use egrapsa::formatters::{Language, SpeakerPlacement};
+use egrapsa::punctuation;
+use egrapsa::text::{TextNode, TextParent};
+
+fn latex(text: &TextParent) -> String {
+    let config = FormatterConfig {
+        language: Language::Greek,
+        ..Default::default()
+    };
+    punctuation::normalize(&text.format_for_latex(&config), &config.punctuation_rules())
+}
+
+#[test]
+fn cast_list_comes_first() {
+    let latex = latex(&tei_fixture("aeschylus_prometheus.xml"));
+
+    let cast_list = latex
+        .find(r"\begin{castlist}{Τὰ τοῦ δράματος πρόσωπα}")
+        .unwrap();
+    assert!(cast_list < latex.find(r"\speaker{ΚΡΑΤΟΣ}").unwrap());
+    assert_eq!(latex.matches(r"\castitem{").count(), 4);
+    assert!(latex.contains(r"\castitem{\textsc{\MakeLowercase{ΒΙΑ}}, \textit{κωφὸν πρόσωπον}}"));
+}
+
+#[test]
+fn speakers_and_stage_directions() {
+    let latex = latex(&tei_fixture("aeschylus_prometheus.xml"));
+
+    assert!(latex.contains(r"\stage{Κράτος καὶ Βία Ἥφαιστός τε Προμηθέα ἄγοντες}"));
+    assert!(latex.contains(r"\speaker{ΚΡΑΤΟΣ}"));
+    assert!(latex.contains(r"\speaker{ΗΦΑΙΣΤΟΣ}"));
+}
+
+#[test]
+fn shared_lines_are_split() {
+    let latex = latex(&tei_fixture("aeschylus_prometheus.xml"));
+
+    assert!(latex.contains(r"\splitline{I}{Ἥφαιστε,}"));
+    assert!(latex.contains(r"\splitline{F}{σοὶ δὲ χρὴ μέλειν ἐπιστολάς.}"));
+    assert_eq!(latex.matches(r"\verseline{").count(), 2);
+}
+
+#[test]
+fn speaker_placement_is_configurable() {
+    let document = |speaker_placement| {
+        let config = FormatterConfig {
+            speaker_placement,
+            ..Default::default()
+        };
+        document(config, "Prometheus", "aeschylus_prometheus.xml")
+    };
+    let centred = document(SpeakerPlacement::Centred);
+    assert!(centred.contains(r"{\centering\textsc{\MakeLowercase{#1}}\par}"));
+    assert!(!centred.contains("pendingspeaker"));
+
+    let margin = document(SpeakerPlacement::Margin);
+    assert!(margin.contains(r"\gdef\pendingspeaker{\marginnote{\textsc{\MakeLowercase{#1}}}}"));
+    assert!(!margin.contains(r"\centering\textsc"));
+}
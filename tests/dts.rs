@@ -0,0 +1,118 @@
+mod common;
+
+use common::{fixture, MockServer};
+use egrapsa::config::TextSourceType;
+use egrapsa::text::TextNode;
+use egrapsa::text_sources::dts::Dts;
+use egrapsa::text_sources::{GetTextError, TextSource};
+
+const PHALARIS: &str = "urn:cts:greekLit:tlg0062.tlg001.perseus-grc2";
+const TIMON: &str = "urn:cts:greekLit:tlg0062.tlg017.perseus-grc2";
+
+// Query values are form encoded, as the client sends them
+fn encode(value: &str) -> String {
+    value.replace(':', "%3A")
+}
+
+fn serve() -> MockServer {
+    MockServer::serve(&[
+        ("/api/dts", fixture("dts/entry.json")),
+        (
+            &format!("/api/dts/documents?id={}&start=1&end=2.1", encode(PHALARIS)),
+            fixture("dts/phalaris_fragment.xml"),
+        ),
+        (
+            &format!("/api/dts/documents?id={}&ref=1", encode(PHALARIS)),
+            fixture("dts/phalaris_fragment.xml"),
+        ),
+        (
+            &format!("/api/dts/collections?id={}", encode(PHALARIS)),
+            fixture("dts/phalaris_collection.json"),
+        ),
+        (
+            &format!("/api/dts/navigation?id={}&down=1", encode(PHALARIS)),
+            fixture("dts/phalaris_navigation.json"),
+        ),
+        (
+            &format!("/api/dts/documents?id={}", encode(TIMON)),
+            fixture("tei/lucian_timon.xml"),
+        ),
+        (
+            "/api/dts/documents?id=lucian-timon",
+            fixture("tei/lucian_timon.xml"),
+        ),
+    ])
+}
+
+#[test]
+fn passage_range_is_read_from_wrapper() {
+    let server = serve();
+    let dts = Dts::with_entry_url(&server.url("/api/dts"));
+
+    let (text, metadata) = dts
+        .get_text_with_metadata(&format!("{PHALARIS}:1-2.1"))
+        .unwrap();
+    let text = TextNode::to_string(&text);
+
+    assert!(text.contains("Ἔπεμψεν ἡμᾶς"));
+    assert!(text.contains("πρόξενος"));
+
+    let metadata = metadata.unwrap();
+    assert_eq!(metadata.title.as_deref(), Some("Φάλαρις"));
+    assert_eq!(metadata.author.as_deref(), Some("Lucian of Samosata"));
+}
+
+#[test]
+fn single_reference_is_requested_with_ref() {
+    let server = serve();
+    let dts = Dts::with_entry_url(&server.url("/api/dts"));
+
+    assert!(dts.get_text(&format!("{PHALARIS}:1")).is_ok());
+}
+
+#[test]
+fn whole_documents_keep_their_header() {
+    let server = serve();
+    let dts = Dts::with_entry_url(&server.url("/api/dts"));
+
+    for id in [TIMON, "dts:lucian-timon"] {
+        let (text, metadata) = dts.get_text_with_metadata(id).unwrap();
+
+        assert!(TextNode::to_string(&text).contains("Ὦ Ζεῦ φίλιε"));
+        assert_eq!(
+            metadata.unwrap().editors,
+            vec![String::from("A. M. Harmon")]
+        );
+    }
+}
+
+#[test]
+fn navigation_lists_references() {
+    let server = serve();
+    let dts = Dts::with_entry_url(&server.url("/api/dts"));
+
+    assert_eq!(dts.references(PHALARIS, 1).unwrap(), vec!["1", "2"]);
+}
+
+#[test]
+fn source_url_selects_the_api() {
+    let server = serve();
+    let source = TextSourceType::Dts.get_source(None, Some(&server.url("/api/dts")));
+
+    assert!(source.get_text(&format!("{PHALARIS}:1")).is_ok());
+}
+
+#[test]
+fn unknown_documents_are_connection_errors() {
+    let server = serve();
+    let dts = Dts::with_entry_url(&server.url("/api/dts"));
+
+    assert!(matches!(
+        dts.get_text(&format!("{PHALARIS}:3")),
+        Err(GetTextError::ConnectionError)
+    ));
+    assert!(matches!(
+        dts.get_text("tlg0062"),
+        Err(GetTextError::InvalidIdentifier)
+    ));
+}
@@ -0,0 +1,47 @@
+mod common;
+
+use common::{document, tei_fixture};
+use egrapsa::config::FormatterConfig;
+use egrapsa::formatters::Language;
+use egrapsa::text::TextNode;
+
+#[test]
+fn epigrams_are_numbered_in_roman_numerals() {
+    let latex = tei_fixture("martial_epigrams.xml").format_for_latex(&FormatterConfig::default());
+
+    assert_eq!(latex.matches(r"\begin{epigram}").count(), 3);
+    assert!(latex.contains("\\begin{epigram}\n\\epigramheading{I}{}\n"));
+    assert!(latex.contains(r"\epigramheading{IV}{Ad lectorem}"));
+    assert!(latex.contains(r"\epigramheading{16a}{}"));
+}
+
+#[test]
+fn epigrams_are_numbered_in_greek_numerals() {
+    let latex = tei_fixture("martial_epigrams.xml").format_for_latex(&FormatterConfig {
+        language: Language::Greek,
+        ..Default::default()
+    });
+
+    assert!(latex.contains(r"\epigramheading{αʹ}{}"));
+    assert!(latex.contains(r"\epigramheading{δʹ}{Ad lectorem}"));
+}
+
+#[test]
+fn ornaments_are_optional() {
+    let document = |epigram_ornaments| {
+        let config = FormatterConfig {
+            epigram_ornaments,
+            ..Default::default()
+        };
+        document(config, "Epigrammata", "martial_epigrams.xml")
+    };
+    let plain = document(false);
+    let ornamented = document(true);
+
+    assert!(plain.contains(r"\newcommand{\epigramornament}{}"));
+    assert!(
+        ornamented.contains(r"\newcommand{\epigramornament}{\par\medskip{\centering\psvectorian")
+    );
+    // Each work and book starts without one
+    assert!(ornamented.contains("\\setcounter{section}{0}\n\\global\\epigramstartedfalse\n"));
+}
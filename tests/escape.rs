@@ -0,0 +1,71 @@
+use egrapsa::config::FormatterConfig;
+use egrapsa::escape::{escape_latex, GLYPH_FALLBACKS};
+use egrapsa::formatters::Counter;
+use egrapsa::text::{
+    Footnote, NumberedHeading, ParagraphNumber, TextNode, TextNodeKind, TextParent,
+};
+
+#[test]
+fn special_characters_are_escaped_once() {
+    assert_eq!(
+        escape_latex(r"#1 \ $2 50% a_b {c} ^ ~ &"),
+        r"\#1 \textbackslash{} \$2 50\% a\_b \{c\} \textasciicircum{} \textasciitilde{} \&"
+    );
+    assert_eq!(escape_latex(r"\#"), r"\textbackslash{}\#");
+}
+
+#[test]
+fn entities_are_decoded() {
+    assert_eq!(
+        escape_latex("&lt;supplied&gt; &amp; &quot;q&quot; &c"),
+        r#"\textless{}supplied\textgreater{} \& "q" \&c"#
+    );
+}
+
+#[test]
+fn glyphs_missing_from_fonts_fall_back() {
+    assert_eq!(escape_latex("CIↄ"), r"CI\rotatebox[origin=c]{180}{c}");
+    assert_eq!(escape_latex("ϛʹ"), "στʹ");
+    for (glyph, fallback) in GLYPH_FALLBACKS {
+        assert!(!fallback.contains(glyph));
+    }
+
+    let config = FormatterConfig {
+        language: egrapsa::formatters::Language::Greek,
+        ..Default::default()
+    };
+    let heading = NumberedHeading {
+        counter: Counter::Epigram,
+        number: Some(String::from("16")),
+        head: None,
+    };
+    assert_eq!(heading.format_for_latex(&config), "{ιστʹ}{}\n");
+}
+
+#[test]
+fn markup_is_not_escaped() {
+    let text = TextParent {
+        name: None,
+        kind: TextNodeKind::Paragraph,
+        subtexts: vec![
+            Box::new(ParagraphNumber(String::from("1"))),
+            Box::new(String::from("Gallia est omnis divisa")),
+            Box::new(Footnote(String::from("Cf. 100% certain"))),
+            Box::new(String::from(" in partes tres")),
+            Box::new(ParagraphNumber(String::from("2"))),
+            Box::new(String::from(" quarum_unam")),
+        ],
+    };
+    let config = FormatterConfig {
+        footnotes: true,
+        ..Default::default()
+    };
+
+    let latex = text.format_for_latex(&config);
+    assert!(latex.contains(r"\refnumber{1}"));
+    assert!(latex.contains(r"\footnote{Cf. 100\% certain.}"));
+    assert!(latex.contains(" in partes tres"));
+    assert!(latex.contains(r"\refnumber{2}"));
+    assert!(latex.contains(r"quarum\_unam"));
+    assert!(!latex.contains("textbackslash"));
+}
@@ -0,0 +1,90 @@
+use egrapsa::config::validation::validate;
+use egrapsa::config::FormatterConfig;
+use egrapsa::formatters::{
+    latex::Latex, GreekHyphenation, Hyphenation, Language, LatinHyphenation, TextFormatter,
+};
+use serde_json::json;
+
+fn preamble(config: FormatterConfig) -> String {
+    let mut formatter = Latex::new();
+    config.apply(&mut formatter);
+    formatter.format().unwrap()
+}
+
+#[test]
+fn patterns_follow_the_style() {
+    let hyphenation = |latin, greek| Hyphenation {
+        latin,
+        greek,
+        exceptions: Vec::new(),
+    };
+
+    assert_eq!(
+        Hyphenation::default().babel_language(&Language::Latin),
+        "latin"
+    );
+    assert_eq!(
+        Hyphenation::default().babel_language(&Language::Greek),
+        "greek.polutoniko"
+    );
+    let classical = hyphenation(LatinHyphenation::Classical, GreekHyphenation::Ancient);
+    assert_eq!(classical.babel_language(&Language::Latin), "classiclatin");
+    assert_eq!(classical.babel_language(&Language::Greek), "greek.ancient");
+    let liturgical = hyphenation(LatinHyphenation::Liturgical, GreekHyphenation::Modern);
+    assert_eq!(
+        liturgical.babel_language(&Language::Latin),
+        "ecclesiasticlatin"
+    );
+
+    let latex = preamble(FormatterConfig {
+        language: Language::Greek,
+        hyphenation: classical,
+        ..Default::default()
+    });
+    assert!(latex.contains(r"\usepackage[greek.ancient]{babel}"));
+    assert!(!latex.contains("babelhyphenation"));
+}
+
+#[test]
+fn exceptions_are_added() {
+    let latex = preamble(FormatterConfig {
+        hyphenation: Hyphenation {
+            exceptions: vec![String::from("Ἀ-χιλ-λεύς"), String::from("Agamemnon")],
+            ..Default::default()
+        },
+        ..Default::default()
+    });
+
+    assert!(latex.contains(r"\usepackage[latin]{babel}"));
+    assert!(latex.contains(r"\babelhyphenation{Ἀ-χιλ-λεύς Agamemnon}"));
+}
+
+#[test]
+fn exceptions_are_checked() {
+    let value = json!({
+        "name": "Ilias",
+        "formatter_type": "Latex",
+        "formatter_config": {
+            "title": null,
+            "author": null,
+            "catchwords": false,
+            "ref_numbers": false,
+            "footnotes": false,
+            "language": "Greek",
+            "hyphenation": { "greek": "Ancient", "exceptions": ["Ἀ-χιλ-λεύς", "δῖος Ἀχιλλεύς"] }
+        },
+        "source_type": "Scaife",
+        "work_infos": [{ "title": "Ilias", "alt_title": null, "identifier": "urn:cts:greekLit:tlg0012.tlg001" }]
+    });
+
+    let diagnostics = validate(&value);
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(
+        diagnostics[0].path,
+        "$.formatter_config.hyphenation.exceptions[1]"
+    );
+    assert_eq!(
+        diagnostics[0].message,
+        "`δῖος Ἀχιλλεύς` is not a single word with hyphens at its breaks"
+    );
+}
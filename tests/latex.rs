@@ -0,0 +1,195 @@
+mod common;
+
+use common::snapshot::assert_snapshot;
+use common::{fixture, MockScaife};
+use egrapsa::config::FormatterConfig;
+use egrapsa::formatters::{latex::Latex, TextFormatter, Work};
+use egrapsa::metadata::Metadata;
+use egrapsa::text::{
+    Footnote, Gap, Highlight, LineNumber, MarginNote, Milestone, ParagraphNumber, TextNode,
+    TextNodeKind, TextParent,
+};
+use egrapsa::text_sources::{scaife::Scaife, TextSource};
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+struct SnapshotWork {
+    title: String,
+    alt_title: Option<String>,
+    identifier: String,
+    fixture: String,
+}
+
+#[derive(Deserialize)]
+struct SnapshotCase {
+    formatter_config: FormatterConfig,
+    works: Vec<SnapshotWork>,
+}
+
+fn format(config: FormatterConfig, works: Vec<Work>) -> String {
+    let mut formatter = Latex::new();
+    config.apply(&mut formatter);
+
+    for work in works {
+        formatter.add_work(work);
+    }
+
+    formatter.format().unwrap()
+}
+
+fn check_case(name: &str) {
+    let case: SnapshotCase = serde_json::from_str(&fixture(&format!("latex/{name}.json"))).unwrap();
+
+    let passages: Vec<_> = case
+        .works
+        .iter()
+        .map(|work| (work.identifier.as_str(), fixture(&work.fixture)))
+        .collect();
+    let server = MockScaife::serve(&passages);
+    let source = Scaife::with_library_url(server.library_url());
+
+    let works = case
+        .works
+        .iter()
+        .map(|work| Work {
+            title: work.title.clone(),
+            alt_title: work.alt_title.clone(),
+            author: None,
+            metadata: Metadata::default(),
+            text: source.get_text(&work.identifier).unwrap(),
+        })
+        .collect();
+
+    assert_snapshot(
+        &format!("latex/{name}.tex"),
+        &format(case.formatter_config, works),
+    );
+}
+
+fn parent(kind: TextNodeKind, subtexts: Vec<Box<dyn TextNode>>) -> Box<dyn TextNode> {
+    Box::new(TextParent {
+        name: None,
+        kind,
+        subtexts,
+    })
+}
+
+#[test]
+fn homer() {
+    check_case("homer");
+}
+
+#[test]
+fn cicero() {
+    check_case("cicero");
+}
+
+#[test]
+fn lucian() {
+    check_case("lucian");
+}
+
+#[test]
+fn anthology() {
+    check_case("anthology");
+}
+
+#[test]
+fn text_nodes() {
+    let config = FormatterConfig {
+        title: Some(String::from("Specimen")),
+        ref_numbers: true,
+        footnotes: true,
+        ..Default::default()
+    };
+
+    let text = TextParent {
+        name: None,
+        kind: TextNodeKind::Book,
+        subtexts: vec![Box::new(TextParent {
+            name: Some(Box::new("De rebus")),
+            kind: TextNodeKind::Section,
+            subtexts: vec![
+                parent(
+                    TextNodeKind::Paragraph,
+                    vec![
+                        Box::new(ParagraphNumber(String::from("1"))),
+                        parent(TextNodeKind::Label, vec![Box::new("Caput")]),
+                        Box::new(
+                            "Gallia est omnis divisa in partes tres, quarum unam incolunt Belgae",
+                        ),
+                        Box::new(Footnote(String::from("Cf. Strabo"))),
+                        Box::new(", aliam Aquitani, tertiam qui ipsorum lingua Celtae"),
+                        Box::new(Gap {
+                            reason: String::from("lost"),
+                            rend: None,
+                        }),
+                        Box::new(Milestone {
+                            unit: String::from("section"),
+                            number: Some(String::from("2")),
+                            ed: None,
+                            resp: None,
+                        }),
+                        Box::new(Milestone {
+                            unit: String::from("page"),
+                            number: Some(String::from("14")),
+                            ed: None,
+                            resp: None,
+                        }),
+                        Box::new(Highlight {
+                            rend: String::from("italics"),
+                            text: Box::new("nostra Galli"),
+                        }),
+                        Box::new(" appellantur."),
+                    ],
+                ),
+                Box::new(TextParent {
+                    name: Some(Box::new("Helvetii")),
+                    kind: TextNodeKind::SubSection,
+                    subtexts: vec![parent(
+                        TextNodeKind::Paragraph,
+                        vec![
+                            Box::new(MarginNote(String::from("3"))),
+                            Box::new("Hi omnes lingua, institutis, legibus inter se differunt"),
+                            parent(TextNodeKind::Symbol, vec![Box::new("III")]),
+                            parent(
+                                TextNodeKind::Choice,
+                                vec![
+                                    parent(TextNodeKind::Abbreviated, vec![Box::new("Caes.")]),
+                                    parent(TextNodeKind::Expandable, vec![Box::new("Caesar")]),
+                                ],
+                            ),
+                            parent(TextNodeKind::Sic, vec![Box::new("differrunt")]),
+                        ],
+                    )],
+                }),
+                parent(
+                    TextNodeKind::BlockQuote,
+                    vec![
+                        parent(
+                            TextNodeKind::Line,
+                            vec![
+                                Box::new(LineNumber(String::from("1"))),
+                                Box::new("arma virumque cano"),
+                            ],
+                        ),
+                        parent(
+                            TextNodeKind::Line,
+                            vec![Box::new("Troiae qui primus ab oris")],
+                        ),
+                    ],
+                ),
+            ],
+        })],
+    };
+
+    let works = vec![Work {
+        title: String::from("Commentarii"),
+        alt_title: Some(String::from("De bello Gallico")),
+        author: None,
+        metadata: Metadata::default(),
+        text,
+    }];
+
+    assert_snapshot("latex/text_nodes.tex", &format(config, works));
+}
@@ -0,0 +1,42 @@
+mod common;
+
+use common::tei_fixture;
+use egrapsa::config::FormatterConfig;
+use egrapsa::formatters::Language;
+use egrapsa::text::TextNode;
+
+fn latex(language: Language) -> String {
+    tei_fixture("pliny_epistulae.xml").format_for_latex(&FormatterConfig {
+        language,
+        ..Default::default()
+    })
+}
+
+#[test]
+fn letters_have_numbered_headings() {
+    let latex = latex(Language::Latin);
+
+    assert!(latex.contains("\\letterheading{Epistola}{I}{C. Plinius Septicio suo}\n"));
+    assert!(latex.contains("\\letterheading{Epistola}{XII}{C. Plinius Calestrio Tironi suo}\n"));
+}
+
+#[test]
+fn greek_letters_have_greek_headings() {
+    let latex = latex(Language::Greek);
+
+    assert!(latex.contains(r"\letterheading{Ἐπιστολή}{ιβʹ}{C. Plinius Calestrio Tironi suo}"));
+}
+
+#[test]
+fn opener_and_closer_are_typeset() {
+    let latex = latex(Language::Latin);
+
+    let dateline = latex.find(r"\dateline{Scr. Romæ}").unwrap();
+    let salute = latex
+        .find(r"\salute{C. PLINIUS CALESTRIO TIRONI SUO S.}")
+        .unwrap();
+    assert!(dateline < salute);
+    let closer = latex.rfind(r"\begin{closer}").unwrap();
+    assert!(closer < latex.rfind(r"\salute{Vale.}").unwrap());
+    assert!(closer < latex.find(r"\signed{C. Plinius}").unwrap());
+}
@@ -0,0 +1,125 @@
+mod common;
+
+use common::{fixture, fixture_path, MockScaife};
+use egrapsa::config::{FormatterConfig, WorkInfo};
+use egrapsa::formatters::{latex::Latex, TextFormatter};
+use egrapsa::metadata::{Metadata, Responsibility};
+use egrapsa::text::TextNode;
+use egrapsa::text_sources::{read_tei_file, scaife::Scaife, TextSource};
+
+const PHALARIS: &str = "urn:cts:greekLit:tlg0062.tlg001.perseus-grc2:1.1-2.1";
+
+// Works are read through a source, which also takes `file:` identifiers
+fn timon_id() -> String {
+    format!("file:{}", fixture_path("tei/lucian_timon.xml").display())
+}
+
+fn work_info(title: Option<&str>, identifier: &str) -> WorkInfo {
+    WorkInfo {
+        title: title.map(String::from),
+        alt_title: None,
+        author: None,
+        identifier: identifier.to_string(),
+    }
+}
+
+#[test]
+fn tei_header_is_read() {
+    let path = fixture_path("tei/lucian_timon.xml");
+    let (text, metadata) = read_tei_file(&path.to_string_lossy()).unwrap();
+
+    assert!(TextNode::to_string(&text).contains("Ὦ Ζεῦ φίλιε"));
+    assert_eq!(
+        metadata.unwrap(),
+        Metadata {
+            title: Some(String::from("Τίμων ἢ Μισάνθρωπος")),
+            author: Some(String::from("Lucian of Samosata")),
+            editors: vec![String::from("A. M. Harmon")],
+            edition: Some(String::from("Perseus Digital Library, version 4.0")),
+            source: Some(String::from(
+                "Lucian Lucian, Works A. M. Harmon William Heinemann London 1915"
+            )),
+            responsibilities: vec![
+                Responsibility {
+                    resp: String::from("Prepared under the supervision of"),
+                    name: String::from("Gregory Crane"),
+                },
+                Responsibility {
+                    resp: String::from("converted to EpiDoc by"),
+                    name: String::from("Perseus Project"),
+                },
+            ],
+            licence: Some(String::from(
+                "Creative Commons Attribution-ShareAlike 4.0 International License"
+            )),
+        }
+    );
+}
+
+#[test]
+fn passages_have_no_header() {
+    let server = MockScaife::serve(&[(PHALARIS, fixture("scaife/lucian_phalaris.xml"))]);
+    let source = Scaife::with_library_url(server.library_url());
+
+    let (_, metadata) = source.get_text_with_metadata(PHALARIS).unwrap();
+    assert_eq!(metadata, None);
+}
+
+#[test]
+fn missing_title_and_author_come_from_header() {
+    let work = work_info(None, &timon_id()).into_work(&Scaife::new());
+
+    assert_eq!(work.title, "Τίμων ἢ Μισάνθρωπος");
+    assert_eq!(work.author.as_deref(), Some("Lucian of Samosata"));
+}
+
+#[test]
+fn config_overrides_header() {
+    let mut info = work_info(Some("Τίμων"), &timon_id());
+    info.author = Some(String::from("Λουκιανός"));
+    let work = info.into_work(&Scaife::new());
+
+    assert_eq!(work.title, "Τίμων");
+    assert_eq!(work.author.as_deref(), Some("Λουκιανός"));
+}
+
+#[test]
+fn identifier_is_the_last_resort_title() {
+    let server = MockScaife::serve(&[(PHALARIS, fixture("scaife/lucian_phalaris.xml"))]);
+    let source = Scaife::with_library_url(server.library_url());
+
+    let work = work_info(None, PHALARIS).into_work(&source);
+    assert_eq!(work.title, PHALARIS);
+    assert_eq!(work.author, None);
+}
+
+#[test]
+fn editors_note_and_author_from_header() {
+    let mut formatter = Latex::new();
+    FormatterConfig {
+        title: Some(String::from("Timon")),
+        ..Default::default()
+    }
+    .apply(&mut formatter);
+    formatter.add_work(work_info(None, &timon_id()).into_work(&Scaife::new()));
+
+    let tex = formatter.format().unwrap();
+    assert!(tex.contains(r"\author{Lucian of Samosata}"));
+    assert!(tex.contains(r"\likechapter{Nota editoris.}"));
+    assert!(tex.contains(
+        r"\noindent\textbf{Τίμων ἢ Μισάνθρωπος.} Perseus Digital Library, version 4.0. Editores: A. M. Harmon."
+    ));
+    assert!(tex
+        .contains("Licentia: Creative Commons Attribution-ShareAlike 4.0 International License."));
+}
+
+#[test]
+fn no_editors_note_without_header() {
+    let server = MockScaife::serve(&[(PHALARIS, fixture("scaife/lucian_phalaris.xml"))]);
+    let source = Scaife::with_library_url(server.library_url());
+
+    let mut formatter = Latex::new();
+    formatter.add_work(work_info(Some("Φάλαρις"), PHALARIS).into_work(&source));
+
+    assert!(!formatter.format().unwrap().contains("Nota editoris"));
+}
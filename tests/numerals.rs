@@ -0,0 +1,59 @@
+use egrapsa::config::FormatterConfig;
+use egrapsa::formatters::{Counter, Language, Numerals};
+use egrapsa::numerals::{self, NumeralStyle};
+
+#[test]
+fn greek_numerals() {
+    assert_eq!(numerals::greek(0), "");
+    assert_eq!(numerals::greek(6), "ϛʹ");
+    assert_eq!(numerals::greek(10), "ιʹ");
+    assert_eq!(numerals::greek(90), "ϙʹ");
+    assert_eq!(numerals::greek(169), "ρξθʹ");
+    assert_eq!(numerals::greek(900), "ϡʹ");
+    assert_eq!(numerals::greek(1800), "͵αωʹ");
+    assert_eq!(numerals::greek(9999), "͵θϡϙθʹ");
+}
+
+#[test]
+fn roman_numerals() {
+    assert_eq!(numerals::roman(0), "");
+    assert_eq!(numerals::roman(14), "XIV");
+    assert_eq!(numerals::roman(1994), "MCMXCIV");
+}
+
+#[test]
+fn styles_follow_the_language() {
+    let config = |language| FormatterConfig {
+        language,
+        ..Default::default()
+    };
+
+    let latin = config(Language::Latin);
+    assert_eq!(latin.numeral_style(Counter::Book), NumeralStyle::Roman);
+    assert_eq!(latin.numeral_style(Counter::Folio), NumeralStyle::Arabic);
+    let greek = config(Language::Greek);
+    assert_eq!(greek.numeral_style(Counter::Epigram), NumeralStyle::Greek);
+    assert_eq!(greek.numeral_style(Counter::Folio), NumeralStyle::Arabic);
+}
+
+#[test]
+fn styles_are_selectable_per_counter() {
+    let config = FormatterConfig {
+        language: Language::Greek,
+        numerals: Numerals {
+            chapters: Some(NumeralStyle::Arabic),
+            folios: Some(NumeralStyle::Greek),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    assert_eq!(config.numeral_style(Counter::Book), NumeralStyle::Greek);
+    assert_eq!(config.numeral_style(Counter::Chapter), NumeralStyle::Arabic);
+    assert_eq!(config.numeral_style(Counter::Folio), NumeralStyle::Greek);
+    assert_eq!(NumeralStyle::Arabic.format(169), "169");
+    assert_eq!(
+        NumeralStyle::Greek.latex_counter("page"),
+        r"\greekalpha{page}"
+    );
+}
@@ -0,0 +1,58 @@
+mod common;
+
+use common::tei_fixture;
+use egrapsa::config::FormatterConfig;
+use egrapsa::text::{TextNode, TextParent};
+
+fn latex(text: &TextParent) -> String {
+    text.format_for_latex(&FormatterConfig {
+        ref_numbers: true,
+        ..Default::default()
+    })
+}
+
+// Spacing around the nodes is irrelevant to LaTeX
+fn without_spaces(latex: &str) -> String {
+    latex.replace(' ', "")
+}
+
+#[test]
+fn line_divisions_are_verse_lines() {
+    let text = tei_fixture("ogl_gregory_carmina.xml");
+    let latex = latex(&text);
+
+    assert!(TextNode::to_string(&text).contains("πειράζοντες"));
+    assert_eq!(latex.matches(r"\verseline{").count(), 3);
+    assert!(latex.contains(r"\verseline{Οἶδ᾽ ὅτι πειράζοντες ἐπὶ σμικρῇ σκαφίδι}"));
+}
+
+#[test]
+fn line_groups_are_stanzas() {
+    let text = tei_fixture("ogl_synesius_hymni.xml");
+    let latex = without_spaces(&latex(&text));
+
+    assert_eq!(latex.matches(r"\verseline{").count(), 4);
+    assert_eq!(latex.matches(r"\begin{stanza}").count(), 2);
+    assert!(latex.contains("ἀοιδάν,}\n\\end{stanza}"));
+    assert!(latex.contains("ὕμνοις}\n\\end{stanza}"));
+}
+
+#[test]
+fn anonymous_blocks_and_segments_are_prose() {
+    let text = tei_fixture("ogl_clement_protrepticus.xml");
+    let plain = TextNode::to_string(&text);
+
+    assert!(plain.contains("Ἀμφίων ὁ Θηβαῖος"));
+    assert!(plain.contains("ᾄδεται χορῷ."));
+    assert!(without_spaces(&latex(&text)).contains("χορῷ.\n\n"));
+}
+
+#[test]
+fn page_breaks_name_their_edition() {
+    let latex = latex(&tei_fixture("ogl_clement_protrepticus.xml"));
+
+    assert!(latex.contains(r"\refnumber{3}"));
+    assert!(latex.contains(r"\refnumber{Potter 1}"));
+    // Facsimile references are only kept in the tree
+    assert!(!latex.contains("archive.org"));
+}
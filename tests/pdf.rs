@@ -0,0 +1,117 @@
+use egrapsa::formatters::latex::{BACK_MATTER_MARKER, WORK_MARKER};
+use egrapsa::pdf::{locate_bad_boxes, locate_errors, BadBox, BoxProblem, LatexError};
+
+// Lines 1-3 are the preamble, works start at lines 4 and 7, back matter at line 10
+fn tex() -> String {
+    [
+        r"\documentclass{book}",
+        r"\begin{document}",
+        r"\maketitle",
+        &format!("{WORK_MARKER}Φάλαρις"),
+        r"\chapter*{Φάλαρις.}",
+        r"Ἔπεμψεν ἡμᾶς",
+        &format!("{WORK_MARKER}Ὄνειρος ἢ Ἀλεκτρυών"),
+        r"\chapter*{Ὄνειρος ἢ Ἀλεκτρυών.}",
+        r"Ἀλλά σε, κάκιστε ἀλεκτρυών",
+        BACK_MATTER_MARKER,
+        r"\tableofcontents",
+        r"\end{document}",
+    ]
+    .join("\n")
+}
+
+#[test]
+fn errors_are_mapped_to_works() {
+    let output = "\
+This is XeTeX, Version 3.141592653-2.6-0.999995 (TeX Live 2023)
+(./lucian.tex
+./lucian.tex:2: Undefined control sequence.
+l.2 \\greekalpa
+./lucian.tex:6: Missing $ inserted.
+./lucian.tex:9: Extra }, or forgotten $.
+./lucian.tex:11: Undefined control sequence.
+";
+
+    assert_eq!(
+        locate_errors(output, &tex()),
+        vec![
+            LatexError {
+                work: None,
+                line: 2,
+                message: String::from("Undefined control sequence."),
+            },
+            LatexError {
+                work: Some(String::from("Φάλαρις")),
+                line: 6,
+                message: String::from("Missing $ inserted."),
+            },
+            LatexError {
+                work: Some(String::from("Ὄνειρος ἢ Ἀλεκτρυών")),
+                line: 9,
+                message: String::from("Extra }, or forgotten $."),
+            },
+            LatexError {
+                work: None,
+                line: 11,
+                message: String::from("Undefined control sequence."),
+            },
+        ]
+    );
+}
+
+#[test]
+fn tectonic_errors_are_recognised() {
+    let output = "note: Running TeX ...\nerror: lucian.tex:4: Undefined control sequence\n";
+
+    let errors = locate_errors(output, &tex());
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].work.as_deref(), Some("Φάλαρις"));
+    assert_eq!(errors[0].line, 4);
+}
+
+#[test]
+fn output_without_errors() {
+    let output = "Output written on lucian.pdf (14 pages).\nTranscript written on lucian.log.\n";
+
+    assert!(locate_errors(output, &tex()).is_empty());
+}
+
+#[test]
+fn bad_boxes_are_mapped_to_works() {
+    let log = "\
+(./lucian.tex
+Overfull \\hbox (12.34567pt too wide) in paragraph at lines 6--6
+[]\\TU/TheanoOldStyle(0)/m/n/12 Ἔπεμψεν ἡμᾶς 
+Underfull \\hbox (badness 10000) in paragraph at lines 8--9
+Underfull \\vbox (badness 10000) has occurred while \\output is active []
+Overfull \\vbox (3.0pt too high) detected at line 11
+";
+
+    assert_eq!(
+        locate_bad_boxes(log, &tex()),
+        vec![
+            BadBox {
+                work: Some(String::from("Φάλαρις")),
+                line: 6,
+                problem: BoxProblem::Overfull,
+                detail: String::from("12.34567pt too wide"),
+            },
+            BadBox {
+                work: Some(String::from("Ὄνειρος ἢ Ἀλεκτρυών")),
+                line: 8,
+                problem: BoxProblem::Underfull,
+                detail: String::from("badness 10000"),
+            },
+            BadBox {
+                work: None,
+                line: 11,
+                problem: BoxProblem::Overfull,
+                detail: String::from("3.0pt too high"),
+            },
+        ]
+    );
+    assert_eq!(
+        locate_bad_boxes(log, &tex())[0].to_string(),
+        "line 6 (in Φάλαρις): overfull box, 12.34567pt too wide"
+    );
+}
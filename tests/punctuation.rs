@@ -0,0 +1,133 @@
+use egrapsa::config::validation::validate;
+use egrapsa::formatters::Language;
+use egrapsa::punctuation::{normalize, LanguageRules, Punctuation, Rules};
+use serde_json::json;
+
+fn latin(text: &str) -> String {
+    normalize(text, &Rules::default_for(&Language::Latin))
+}
+
+fn greek(text: &str) -> String {
+    normalize(text, &Rules::default_for(&Language::Greek))
+}
+
+#[test]
+fn marks_are_followed_by_one_space() {
+    assert_eq!(
+        latin("Gallia est omnis divisa ,in partes tres ."),
+        "Gallia est omnis divisa, in partes tres."
+    );
+    assert_eq!(latin("Quid ?Nihil !"), "Quid? Nihil!");
+    assert_eq!(latin("Cf.  Div.   in Verr."), "Cf. Div. in Verr.");
+    assert_eq!(latin("Sed ...nihil?!"), "Sed... nihil?!");
+    assert_eq!(latin("(vide p.12, v.3)"), "(vide p. 12, v. 3)");
+}
+
+#[test]
+fn numbers_are_kept_together() {
+    assert_eq!(latin("1.5 et 10:30, 3,000."), "1.5 et 10:30, 3,000.");
+}
+
+#[test]
+fn greek_marks_are_normalised() {
+    assert_eq!(greek("τί δὲ τοῦτο ;"), "τί δὲ τοῦτο;");
+    assert_eq!(greek("τί δὲ τοῦτο\u{37E}"), "τί δὲ τοῦτο;");
+    assert_eq!(greek("ἔρωτος\u{387}ἃ"), "ἔρωτος· ἃ");
+    assert_eq!(greek("ἐστίν · ἀπὸ"), "ἐστίν· ἀπὸ");
+}
+
+#[test]
+fn greek_elision_uses_one_apostrophe() {
+    assert_eq!(greek("πολλὰς δ᾽ ἰφθίμους"), "πολλὰς δ’ ἰφθίμους");
+    assert_eq!(
+        greek("μυρί' Ἀχαιοῖς ἄλγεʼ ἔθηκε"),
+        "μυρί’ Ἀχαιοῖς ἄλγε’ ἔθηκε"
+    );
+    // Latin apostrophes are no elision
+    assert_eq!(latin("Caesar' s"), "Caesar' s");
+}
+
+#[test]
+fn dashes_are_closed_up() {
+    assert_eq!(latin("verbum — verbum"), "verbum---verbum");
+    assert_eq!(latin("verbum --- verbum"), "verbum---verbum");
+    assert_eq!(latin("pp. 1--3"), "pp. 1--3");
+
+    let rules = Rules {
+        spaced_dashes: true,
+        ..Rules::default_for(&Language::Latin)
+    };
+    assert_eq!(normalize("verbum—verbum", &rules), "verbum~--- verbum");
+}
+
+#[test]
+fn straight_quotes_follow_the_language() {
+    assert_eq!(
+        latin(r#"dixit "veni, vidi, vici" et"#),
+        "dixit “veni, vidi, vici” et"
+    );
+    assert_eq!(greek(r#"ἔφη "χαῖρε." καὶ"#), "ἔφη «χαῖρε.» καὶ");
+    assert_eq!(latin(r#"("veni")"#), "(“veni”)");
+}
+
+#[test]
+fn commands_are_left_alone() {
+    assert_eq!(
+        latin(r"videtur.\footnote{Cf. Strabo.} Inveteravit"),
+        r"videtur.\footnote{Cf. Strabo.} Inveteravit"
+    );
+    assert_eq!(
+        latin(r"a\,b\;c\\d \refnumber{p.1}"),
+        r"a\,b\;c\\d \refnumber{p. 1}"
+    );
+    assert_eq!(latin(r"sumpsit.\textit{XL}"), r"sumpsit. \textit{XL}");
+}
+
+#[test]
+fn rules_are_configured_per_language() {
+    let punctuation = Punctuation {
+        greek: LanguageRules {
+            spaced_marks: Some(String::from(";:?!")),
+            elision_mark: Some('ʼ'),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    let rules = punctuation.rules(&Language::Greek);
+    assert_eq!(normalize("τί δ' ἐστί;", &rules), r"τί δʼ ἐστί\,;");
+    assert_eq!(
+        punctuation.rules(&Language::Latin),
+        Rules::default_for(&Language::Latin)
+    );
+}
+
+#[test]
+fn punctuation_keys_are_checked() {
+    let value = json!({
+        "name": "Ilias",
+        "formatter_type": "Latex",
+        "formatter_config": {
+            "title": null,
+            "author": null,
+            "catchwords": false,
+            "ref_numbers": false,
+            "footnotes": false,
+            "language": "Greek",
+            "punctuation": { "greek": { "elision": "ʼ" } }
+        },
+        "source_type": "Scaife",
+        "work_infos": [{ "title": "Ilias", "alt_title": null, "identifier": "urn:cts:greekLit:tlg0012.tlg001" }]
+    });
+
+    let diagnostics = validate(&value);
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(
+        diagnostics[0].path,
+        "$.formatter_config.punctuation.greek.elision"
+    );
+    assert_eq!(
+        diagnostics[0].message,
+        "unknown key `elision`, did you mean `elision_mark`?"
+    );
+}
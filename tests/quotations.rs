@@ -0,0 +1,77 @@
+mod common;
+
+use common::{document, tei_fixture};
+use egrapsa::config::FormatterConfig;
+use egrapsa::formatters::Language;
+use egrapsa::punctuation::{LanguageRules, Punctuation, QuoteStyle};
+use egrapsa::text::TextNode;
+
+#[test]
+fn marks_alternate_by_depth() {
+    assert_eq!(QuoteStyle::English.marks(0), ["“", "”"]);
+    assert_eq!(QuoteStyle::English.marks(1), ["‘", "’"]);
+    assert_eq!(QuoteStyle::German.marks(0), ["„", "“"]);
+    assert_eq!(QuoteStyle::German.marks(1), ["‚", "‘"]);
+    assert_eq!(QuoteStyle::Guillemets.marks(1), ["“", "”"]);
+    assert_eq!(QuoteStyle::Greek.marks(1), ["‹", "›"]);
+    assert_eq!(QuoteStyle::Greek.marks(2), QuoteStyle::Greek.marks(0));
+}
+
+#[test]
+fn quotations_are_nested() {
+    let latex = tei_fixture("livy_quotations.xml").format_for_latex(&FormatterConfig::default());
+    assert!(latex.contains(r"\enquote{Sic deinde, \enquote{quicumque alius transiliet}"));
+
+    let config = FormatterConfig::default();
+    let latex = document(config, "Ab urbe condita", "livy_quotations.xml");
+    assert!(latex.contains("dicitur: “Sic deinde, ‘quicumque alius transiliet’, mœnia mea.”"));
+    assert!(latex.contains("Nuntius “Tullus ‘vos “hostes” vocat’ inquit”."));
+    assert!(!latex.contains(r"\enquote"));
+}
+
+#[test]
+fn only_direct_speech_is_quoted() {
+    let config = FormatterConfig::default();
+    let latex = document(config, "Ab urbe condita", "livy_quotations.xml");
+
+    assert!(latex.contains("Legati nihil se mandati habere responderunt."));
+    assert!(latex.contains("Tum Horatius quid hoc est? rogavit."));
+    assert!(latex.contains("Abi hinc cum immaturo amore ad sponsum."));
+    assert!(!latex.contains("“Abi"));
+}
+
+#[test]
+fn styles_are_chosen_for_each_language() {
+    let punctuation = Punctuation {
+        latin: LanguageRules {
+            quotes: Some(QuoteStyle::German),
+            ..Default::default()
+        },
+        greek: LanguageRules {
+            quotes: Some(QuoteStyle::English),
+            ..Default::default()
+        },
+    };
+
+    let config = FormatterConfig {
+        punctuation: punctuation.clone(),
+        ..Default::default()
+    };
+    let latex = document(config, "Ab urbe condita", "livy_quotations.xml");
+    assert!(latex.contains("„Sic deinde, ‚quicumque alius transiliet‘, mœnia mea.“"));
+
+    let config = FormatterConfig {
+        language: Language::Greek,
+        ..Default::default()
+    };
+    let latex = document(config, "Ab urbe condita", "livy_quotations.xml");
+    assert!(latex.contains("«Sic deinde, ‹quicumque alius transiliet›, mœnia mea.»"));
+
+    let config = FormatterConfig {
+        language: Language::Greek,
+        punctuation,
+        ..Default::default()
+    };
+    let latex = document(config, "Ab urbe condita", "livy_quotations.xml");
+    assert!(latex.contains("“Sic deinde, ‘quicumque alius transiliet’, mœnia mea.”"));
+}
@@ -0,0 +1,173 @@
+mod common;
+
+use common::{fixture, MockScaife};
+use egrapsa::config::FormatterConfig;
+use egrapsa::formatters::Language;
+use egrapsa::punctuation;
+use egrapsa::text::{TextNode, TextNodeKind, TextParent};
+use egrapsa::text_sources::scaife::Scaife;
+use egrapsa::text_sources::{cache::Cache, GetTextError, TextSource};
+
+const HOMER: &str = "urn:cts:greekLit:tlg0012.tlg001.perseus-grc2:1.1-1.7";
+const CICERO: &str = "urn:cts:latinLit:phi0474.phi005.perseus-lat2:1.1.1-1.1.2";
+const GALLUS: &str = "urn:cts:greekLit:tlg0062.tlg019.perseus-grc2:1-2";
+const PHALARIS: &str = "urn:cts:greekLit:tlg0062.tlg001.perseus-grc2:1.1-2.1";
+const ANTHOLOGY: &str = "urn:cts:greekLit:tlg7000.tlg001.perseus-grc2:5.169-5.170";
+
+fn get_text(urn: &str, fixture_name: &str) -> TextParent {
+    let server = MockScaife::serve(&[(urn, fixture(&format!("scaife/{fixture_name}.xml")))]);
+    Scaife::with_library_url(server.library_url())
+        .get_text(urn)
+        .unwrap()
+}
+
+fn latex(text: &TextParent, language: Language) -> String {
+    let config = FormatterConfig {
+        footnotes: true,
+        language,
+        ..Default::default()
+    };
+
+    punctuation::normalize(&text.format_for_latex(&config), &config.punctuation_rules())
+}
+
+#[test]
+fn homer_verse_lines() {
+    let text = get_text(HOMER, "homer_iliad");
+    assert!(matches!(text.kind, TextNodeKind::Book));
+
+    let plain = text.to_string();
+    assert!(plain.contains("μῆνιν ἄειδε θεὰ"));
+    assert!(plain.contains("Πηληϊάδεω Ἀχιλῆος"));
+    assert!(plain.contains("πᾶσι"));
+    assert!(plain.contains("δῖος Ἀχιλλεύς."));
+
+    let formatted = latex(&text, Language::Greek);
+    assert!(formatted.contains(r"Βιβλίον \greekalpha{section}"));
+    assert_eq!(formatted.matches(r"\verseline{").count(), 7);
+    assert!(formatted.contains(r"\refnumber{1}"));
+    // Only every fifth line is numbered
+    assert!(formatted.contains(r"\verseline{\versenumber{5}οἰωνοῖσί"));
+    assert!(!formatted.contains(r"\versenumber{4}"));
+}
+
+#[test]
+fn cicero_prose_with_editorial_markup() {
+    let text = get_text(CICERO, "cicero_verrines");
+    assert!(matches!(text.kind, TextNodeKind::Book));
+
+    let plain = text.to_string();
+    assert!(plain.contains("Quod erat optandum maxime"));
+    assert!(plain.contains("Verres"));
+
+    let formatted = latex(&text, Language::Latin);
+    assert!(formatted.contains(r"\refnumber{p. 1}"));
+    assert!(formatted.contains(r"\refnumber{10}"));
+    assert!(formatted.contains(r"\refnumber{2}"));
+    assert!(formatted.contains("Div. in Cæc."));
+    assert!(formatted.contains(r"\footnote{lacuna.}"));
+    assert!(formatted.contains(r"\textit{pecuniæ magnitudine}"));
+    assert!(formatted.contains(r"\textit{XL}"));
+    // Abbreviations are always replaced by their expansions
+    assert!(formatted.contains("Gaius Verres"));
+    assert!(!formatted.contains("C. Verres"));
+}
+
+#[test]
+fn lucian_dialogue_speakers() {
+    let text = get_text(GALLUS, "lucian_gallus");
+    let formatted = latex(&text, Language::Greek);
+
+    assert_eq!(formatted.matches(r"\speaker{ΜΙΚΥΛΛΟΣ}").count(), 2);
+    assert_eq!(formatted.matches(r"\speaker{ΑΛΕΚΤΡΥΩΝ}").count(), 2);
+    assert!(formatted.contains(r"\textbf{Ζεῦ} "));
+    assert!(formatted.contains("φθάσαντα τὴν νύκτα"));
+    assert!(formatted.contains("[...]"));
+    assert!(formatted.contains("desunt nonnulla.}"));
+    // <sic> is dropped, only the correction is kept
+    assert_eq!(formatted.matches("Πυθαγόρας").count(), 1);
+}
+
+#[test]
+fn phalaris_headed_books() {
+    let text = get_text(PHALARIS, "lucian_phalaris");
+    let formatted = latex(&text, Language::Greek);
+
+    assert_eq!(formatted.matches(r"\stepcounter{section}").count(), 2);
+    assert!(formatted.contains(r"\section*{Βιβλίον \greekalpha{section}. \\Φάλαρις πρῶτος.}"));
+    assert!(formatted.contains(r"\section*{Βιβλίον \greekalpha{section}. \\Φάλαρις δεύτερος.}"));
+    assert!(formatted.contains("ἰδίᾳ"));
+    // Page milestones carry no information for the reader
+    assert!(!formatted.contains(r"\refnumber{2}"));
+}
+
+#[test]
+fn greek_anthology_epigrams() {
+    let text = get_text(ANTHOLOGY, "greek_anthology");
+    let formatted = latex(&text, Language::Greek);
+
+    assert!(formatted.contains("Ἡδὺ θέρους διψῶντι χιὼν ποτόν"));
+    assert!(formatted.contains(r"\begin{displayquote}"));
+    assert!(formatted.contains("Ascl. 1 G."));
+    assert!(formatted.contains("μέλι"));
+    assert!(!formatted.contains("μ.."));
+}
+
+#[test]
+fn every_fixture_is_served_by_one_library() {
+    let server = MockScaife::serve(&[
+        (HOMER, fixture("scaife/homer_iliad.xml")),
+        (CICERO, fixture("scaife/cicero_verrines.xml")),
+        (GALLUS, fixture("scaife/lucian_gallus.xml")),
+        (PHALARIS, fixture("scaife/lucian_phalaris.xml")),
+        (ANTHOLOGY, fixture("scaife/greek_anthology.xml")),
+    ]);
+    let source = Scaife::with_library_url(server.library_url());
+
+    for urn in [HOMER, CICERO, GALLUS, PHALARIS, ANTHOLOGY] {
+        let text = source.get_text(urn).unwrap();
+        assert!(matches!(text.kind, TextNodeKind::Book), "{urn}");
+    }
+}
+
+#[test]
+fn missing_passage_is_a_connection_error() {
+    let server = MockScaife::serve(&[]);
+    let result = Scaife::with_library_url(server.library_url()).get_text(HOMER);
+
+    assert!(matches!(result, Err(GetTextError::ConnectionError)));
+}
+
+#[test]
+#[should_panic(expected = "Unexpected tag found inside section: <unknown>")]
+fn unknown_element_is_rejected() {
+    let body = fixture("scaife/homer_iliad.xml")
+        .replace("<l n=\"4\">", "<unknown>ἄγνωστον</unknown><l n=\"4\">");
+    let server = MockScaife::serve(&[(HOMER, body)]);
+
+    let _ = Scaife::with_library_url(server.library_url()).get_text(HOMER);
+}
+
+#[test]
+fn fetch_stores_replies_without_parsing() {
+    let body = fixture("scaife/homer_iliad.xml")
+        .replace("<l n=\"4\">", "<unknown>ἄγνωστον</unknown><l n=\"4\">");
+    let server = MockScaife::serve(&[(HOMER, body.clone())]);
+    let cache_dir = std::env::temp_dir().join(format!("egrapsa-fetch-{}", std::process::id()));
+    let cache = Cache::new(cache_dir.clone());
+
+    Scaife::with_library_url(server.library_url())
+        .with_cache(Some(cache.clone()))
+        .fetch(HOMER)
+        .unwrap();
+    assert_eq!(cache.get("scaife", HOMER), Some(body));
+
+    let _ = std::fs::remove_dir_all(cache_dir);
+}
+
+#[test]
+fn unknown_identifier_prefix_is_an_error() {
+    let result = Scaife::new().get_text("tlg0012.tlg001.perseus-grc2:1.1-1.7");
+
+    assert!(matches!(result, Err(GetTextError::InvalidIdentifier)));
+}
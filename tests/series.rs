@@ -0,0 +1,111 @@
+mod common;
+
+use common::snapshot::assert_snapshot;
+use egrapsa::config::FormatterConfig;
+use egrapsa::formatters::{latex::Latex, TextFormatter, Volume, Work};
+use egrapsa::metadata::Metadata;
+use egrapsa::series::{series_contents, split_volumes, volume_path, VolumeBudget};
+use egrapsa::text::{TextNode, TextNodeKind, TextParent};
+use std::path::Path;
+
+fn work(title: &str, words: usize) -> Work {
+    let text: Vec<Box<dyn TextNode>> = vec![Box::new("verbum ".repeat(words))];
+
+    Work {
+        title: String::from(title),
+        alt_title: None,
+        author: None,
+        metadata: Metadata::default(),
+        text: TextParent {
+            name: None,
+            kind: TextNodeKind::Book,
+            subtexts: text,
+        },
+    }
+}
+
+#[test]
+fn works_are_split_at_boundaries() {
+    let works = vec![
+        work("Phalaris", 400),
+        work("Hippias", 300),
+        work("Electrum", 200),
+        work("Nigrinus", 900),
+        work("Demonax", 100),
+    ];
+
+    assert_eq!(
+        series_contents(&split_volumes(works, VolumeBudget::Words(1000))),
+        vec![
+            vec!["Phalaris", "Hippias", "Electrum"],
+            vec!["Nigrinus", "Demonax"],
+        ]
+    );
+}
+
+#[test]
+fn long_works_get_their_own_volume() {
+    let works = vec![
+        work("Phalaris", 100),
+        work("Ilias", 5000),
+        work("Hippias", 100),
+    ];
+
+    assert_eq!(
+        series_contents(&split_volumes(works, VolumeBudget::Pages(2))),
+        vec![vec!["Phalaris"], vec!["Ilias"], vec!["Hippias"]]
+    );
+}
+
+#[test]
+fn volume_paths() {
+    assert_eq!(
+        volume_path(Path::new("out/lucian.tex"), "2"),
+        Path::new("out/lucian-2.tex")
+    );
+    assert_eq!(
+        volume_path(Path::new("lucian"), "spines"),
+        Path::new("lucian-spines")
+    );
+}
+
+fn formatter() -> Latex {
+    let mut formatter = Latex::new();
+    FormatterConfig {
+        title: Some(String::from("Opera Omnia")),
+        author: Some(String::from("Lucianus Samosatensis")),
+        ..Default::default()
+    }
+    .apply(&mut formatter);
+
+    formatter
+}
+
+#[test]
+fn last_volume_has_cumulative_index() {
+    let volumes = split_volumes(
+        vec![
+            work("Phalaris", 10),
+            work("Hippias", 10),
+            work("Electrum", 10),
+        ],
+        VolumeBudget::Words(20),
+    );
+    let contents = series_contents(&volumes);
+
+    let mut formatter = formatter();
+    formatter.set_volume(Volume {
+        number: 2,
+        series_contents: contents,
+    });
+    for work in volumes.into_iter().nth(1).unwrap() {
+        formatter.add_work(work);
+    }
+
+    assert_snapshot("series/last_volume.tex", &formatter.format().unwrap());
+}
+
+#[test]
+fn spine_labels() {
+    assert_snapshot("series/spines.tex", &formatter().format_spine_labels(3));
+}
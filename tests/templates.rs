@@ -0,0 +1,107 @@
+mod common;
+
+use common::fixture_path;
+use egrapsa::config::validation::validate;
+use egrapsa::config::FormatterConfig;
+use egrapsa::formatters::templates::Templates;
+use egrapsa::formatters::{latex::Latex, TextFormatter, Work};
+use egrapsa::metadata::Metadata;
+use egrapsa::text::{TextNodeKind, TextParent};
+use serde_json::json;
+
+fn formatter(config: FormatterConfig) -> Latex {
+    let mut formatter = Latex::new();
+    config.apply(&mut formatter);
+    for title in ["Ars amatoria", "Remedia amoris"] {
+        formatter.add_work(Work {
+            title: String::from(title),
+            alt_title: None,
+            author: Some(String::from("Ovidius")),
+            metadata: Metadata::default(),
+            text: TextParent {
+                name: None,
+                kind: TextNodeKind::Simple,
+                subtexts: Vec::new(),
+            },
+        });
+    }
+
+    formatter
+}
+
+fn config(templates: &str) -> serde_json::Value {
+    json!({
+        "name": "Ovidius",
+        "formatter_type": "Latex",
+        "formatter_config": {
+            "title": null,
+            "author": null,
+            "catchwords": false,
+            "ref_numbers": false,
+            "footnotes": false,
+            "language": "Latin",
+            "templates": fixture_path(templates)
+        },
+        "source_type": "Scaife",
+        "work_infos": [{ "title": "Ars amatoria", "alt_title": null, "identifier": "urn:cts:latinLit:phi0959.phi004" }]
+    })
+}
+
+#[test]
+fn built_in_templates_are_valid() {
+    assert!(Templates::load(None).is_ok());
+
+    let latex = formatter(FormatterConfig::default()).format().unwrap();
+    assert!(latex.contains(r"\chapter*{Ars amatoria.}"));
+    assert!(latex.contains(r"\chapter*{Remedia amoris.}"));
+    assert!(latex.trim_end().ends_with(r"\end{document}"));
+}
+
+#[test]
+fn templates_can_be_overridden() {
+    let latex = formatter(FormatterConfig {
+        templates: Some(fixture_path("templates/press")),
+        ..Default::default()
+    })
+    .format()
+    .unwrap();
+
+    assert!(latex.contains(r"\chapter*{Ars amatoria}"));
+    assert!(latex.contains(r"\noindent\textsc{Ovidius}\par"));
+    assert!(latex.contains("\\clearpage\n\\chapter*{Remedia amoris}"));
+    assert!(!latex.contains("The press sets"));
+    // Parts without a file of their own keep the built-in templates
+    assert!(latex.contains(r"\documentclass"));
+    assert!(latex.contains(r"\tableofcontents"));
+}
+
+#[test]
+fn render_errors_are_returned() {
+    let error = formatter(FormatterConfig {
+        templates: Some(fixture_path("templates/failing")),
+        ..Default::default()
+    })
+    .format()
+    .unwrap_err();
+
+    assert!(error
+        .to_string()
+        .starts_with("template `work.tex` could not be rendered"));
+}
+
+#[test]
+fn template_directories_are_checked() {
+    assert_eq!(validate(&config("templates/press")), vec![]);
+
+    let diagnostics = validate(&config("templates/missing"));
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].path, "$.formatter_config.templates");
+    assert!(diagnostics[0].message.ends_with("does not exist"));
+
+    let diagnostics = validate(&config("templates/broken"));
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].path, "$.formatter_config.templates");
+    assert!(diagnostics[0]
+        .message
+        .starts_with("template `title.tex` is invalid"));
+}
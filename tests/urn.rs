@@ -0,0 +1,152 @@
+use egrapsa::urn::{CtsUrn, Passage, Reference, Subreference, UrnError};
+
+fn urn(text: &str) -> CtsUrn {
+    text.parse().unwrap()
+}
+
+fn reference(text: &str) -> Reference {
+    text.parse().unwrap()
+}
+
+fn passage(text: &str) -> Passage {
+    text.parse().unwrap()
+}
+
+#[test]
+fn full_urn_is_parsed() {
+    let urn = urn("urn:cts:greekLit:tlg0012.tlg001.perseus-grc2:1.1-1.303");
+
+    assert_eq!(urn.namespace, "greekLit");
+    assert_eq!(urn.text_group, "tlg0012");
+    assert_eq!(urn.work.as_deref(), Some("tlg001"));
+    assert_eq!(urn.version.as_deref(), Some("perseus-grc2"));
+    assert_eq!(urn.exemplar, None);
+    assert_eq!(
+        urn.passage,
+        Some(Passage {
+            start: reference("1.1"),
+            end: Some(reference("1.303")),
+        })
+    );
+}
+
+#[test]
+fn partial_urns_are_parsed() {
+    let group = urn("urn:cts:greekLit:tlg0062");
+    assert_eq!(group.work, None);
+    assert!(!group.is_edition());
+
+    let exemplar = urn("urn:cts:latinLit:phi0474.phi005.perseus-lat2.ex1");
+    assert_eq!(exemplar.exemplar.as_deref(), Some("ex1"));
+    assert!(exemplar.is_edition());
+    assert_eq!(exemplar.passage, None);
+}
+
+#[test]
+fn subreferences_are_parsed() {
+    assert_eq!(
+        reference("1.1@μῆνιν").subreference,
+        Some(Subreference {
+            text: String::from("μῆνιν"),
+            index: 1,
+        })
+    );
+    assert_eq!(reference("1.2@ἄλγε᾽[2]").subreference.unwrap().index, 2);
+}
+
+#[test]
+fn urns_are_written_back() {
+    for text in [
+        "urn:cts:greekLit:tlg0062",
+        "urn:cts:greekLit:tlg0012.tlg001.perseus-grc2:1.1-1.303",
+        "urn:cts:greekLit:tlg0012.tlg001.perseus-grc2:1.1@μῆνιν-1.2@ἄλγε᾽[2]",
+        "urn:cts:latinLit:phi0474.phi005.perseus-lat2.ex1:pr",
+    ] {
+        assert_eq!(urn(text).to_string(), text);
+    }
+
+    // The first occurrence is the default
+    assert_eq!(
+        urn("urn:cts:greekLit:tlg0012.tlg001.perseus-grc2:1.1@μῆνιν[1]").to_string(),
+        "urn:cts:greekLit:tlg0012.tlg001.perseus-grc2:1.1@μῆνιν"
+    );
+}
+
+#[test]
+fn malformed_urns_are_rejected() {
+    let error = |text: &str| text.parse::<CtsUrn>().unwrap_err();
+
+    assert_eq!(error("tlg0012.tlg001"), UrnError::MissingPrefix);
+    assert_eq!(error("urn:cts:"), UrnError::MissingNamespace);
+    assert_eq!(error("urn:cts:greekLit"), UrnError::MissingTextGroup);
+    assert_eq!(
+        error("urn:cts:greekLit:tlg0012.tlg001.perseus-grc2.ex1.more"),
+        UrnError::InvalidWork(String::from("tlg0012.tlg001.perseus-grc2.ex1.more"))
+    );
+    assert_eq!(
+        error("urn:cts:greekLit:tlg0012.tlg001.perseus-grc2:1..2"),
+        UrnError::InvalidPassage(String::from("1..2"))
+    );
+    assert_eq!(
+        error("urn:cts:greekLit:tlg0012.tlg001.perseus-grc2:1.1@"),
+        UrnError::InvalidPassage(String::from("1.1@"))
+    );
+    assert_eq!(
+        error("urn:cts:greekLit:tlg0012.tlg001.perseus-grc2:1:2"),
+        UrnError::TooManyComponents
+    );
+}
+
+#[test]
+fn references_are_ordered() {
+    assert!(reference("1.9") < reference("1.10"));
+    assert!(reference("1.303") < reference("2.1"));
+    assert!(reference("1") < reference("1.1"));
+    assert!(reference("12") < reference("12a"));
+    assert!(reference("pr") < reference("1"));
+    assert!(reference("1.1@μῆνιν") > reference("1.1"));
+}
+
+#[test]
+fn references_contain_their_parts() {
+    assert!(reference("1").contains(&reference("1.50")));
+    assert!(reference("1.50").contains(&reference("1.50@θεά")));
+    assert!(!reference("1.50").contains(&reference("1")));
+    assert!(!reference("1").contains(&reference("2.1")));
+}
+
+#[test]
+fn passages_contain_references() {
+    let iliad_1 = passage("1.1-1.303");
+
+    assert!(iliad_1.contains(&reference("1.50")));
+    assert!(iliad_1.contains(&reference("1.303")));
+    assert!(iliad_1.contains(&reference("1.303@ἐρωήσει")));
+    assert!(!iliad_1.contains(&reference("1.304")));
+    assert!(!iliad_1.contains(&reference("2.3")));
+    // Only part of the first book is included
+    assert!(!iliad_1.contains(&reference("1")));
+
+    assert!(passage("1-2").contains(&reference("2.13")));
+    assert!(passage("1.1-2.13").contains_passage(&passage("1.10-2.1")));
+}
+
+#[test]
+fn passage_order_is_checked() {
+    assert!(passage("1.1-1.303").is_ordered());
+    assert!(passage("prologue-38").is_ordered());
+    assert!(!passage("2.1-1.5").is_ordered());
+}
+
+#[test]
+fn urns_contain_urns() {
+    let lucian = urn("urn:cts:greekLit:tlg0062");
+    let phalaris = urn("urn:cts:greekLit:tlg0062.tlg001.perseus-grc2:1.1-2.13");
+
+    assert!(lucian.contains(&phalaris));
+    assert!(!phalaris.contains(&lucian));
+    assert!(phalaris.without_passage().contains(&phalaris));
+    assert!(phalaris.contains(&urn("urn:cts:greekLit:tlg0062.tlg001.perseus-grc2:1.5")));
+    assert!(!phalaris.contains(&urn("urn:cts:greekLit:tlg0062.tlg001.perseus-eng2:1.5")));
+    assert!(!lucian.contains(&urn("urn:cts:greekLit:tlg0012.tlg001")));
+}
@@ -0,0 +1,51 @@
+mod common;
+
+use common::tei_fixture;
+use egrapsa::config::FormatterConfig;
+use egrapsa::text::TextNode;
+
+fn latex(line_number_interval: Option<usize>) -> String {
+    tei_fixture("ovid_amores.xml").format_for_latex(&FormatterConfig {
+        ref_numbers: true,
+        line_number_interval,
+        ..Default::default()
+    })
+}
+
+#[test]
+fn pentameters_are_indented() {
+    let latex = latex(None);
+
+    assert!(latex.contains(r"\verseline{Cum sit opus"));
+    assert!(latex.contains(r"\verseline[\verseindent]{\versenumber{10}vindicat"));
+    assert!(latex.contains(r"\verseline{Quis probet"));
+    assert!(latex.contains(r"\verseline[\verseindent]{lege pharetrat"));
+}
+
+#[test]
+fn every_fifth_line_is_numbered_by_default() {
+    let latex = latex(None);
+
+    assert_eq!(latex.matches(r"\versenumber").count(), 1);
+    assert!(latex.contains(r"\versenumber{10}"));
+}
+
+#[test]
+fn line_number_interval_is_configurable() {
+    let every_third = latex(Some(3));
+    assert_eq!(every_third.matches(r"\versenumber").count(), 2);
+    assert!(every_third.contains(r"\versenumber{9}"));
+    assert!(every_third.contains(r"\versenumber{12}"));
+
+    assert!(!latex(Some(0)).contains(r"\versenumber"));
+}
+
+#[test]
+fn stanzas_keep_their_lines() {
+    let text = tei_fixture("ovid_amores.xml");
+    let latex = latex(None);
+
+    assert!(TextNode::to_string(&text).contains("lacerta sibi?"));
+    assert_eq!(latex.matches(r"\begin{stanza}").count(), 2);
+    assert_eq!(latex.matches(r"\end{stanza}").count(), 2);
+}
@@ -0,0 +1,128 @@
+mod common;
+
+use common::document;
+use egrapsa::config::validation::validate;
+use egrapsa::config::FormatterConfig;
+use egrapsa::formatters::Language;
+use egrapsa::text::{Gap, TextNode};
+use egrapsa::vocabulary::{Vocabulary, Word};
+use serde_json::json;
+
+#[test]
+fn words_follow_the_language() {
+    let config = |language| FormatterConfig {
+        language,
+        ..Default::default()
+    };
+
+    assert_eq!(config(Language::Latin).word(Word::Book), "Liber");
+    assert_eq!(config(Language::Greek).word(Word::Book), "Βιβλίον");
+    assert_eq!(config(Language::Greek).word(Word::TheEnd), "FINIS");
+    assert_eq!(config(Language::Latin).word(Word::Chapter), "");
+}
+
+#[test]
+fn words_can_be_overridden() {
+    let config = FormatterConfig {
+        vocabulary: Vocabulary {
+            book: Some(String::from("Book")),
+            letter: Some(String::from("Letter")),
+            contents: Some(String::from("Contents")),
+            the_end: Some(String::from("The End")),
+            end_of_contents: Some(String::from("End of Contents")),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let latex = document(config, "Epistulae", "pliny_epistulae.xml");
+
+    assert!(latex.contains(r"\section*{Book \Roman{section}.}"));
+    assert!(latex.contains(r"\letterheading{Letter}{XII}"));
+    assert!(latex.contains(r"\renewcommand{\contentsname}{Contents.}"));
+    assert!(latex.contains(r"\textbf{The End.}"));
+    assert!(latex.contains(r"\textbf{End of Contents.}"));
+    assert!(!latex.contains("FINIS"));
+}
+
+#[test]
+fn words_are_escaped() {
+    let config = FormatterConfig {
+        vocabulary: Vocabulary {
+            letter: Some(String::from("Letter #")),
+            contents: Some(String::from("Notes & Sources")),
+            the_end: Some(String::from("100%")),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let latex = document(config, "Epistulae", "pliny_epistulae.xml");
+
+    assert!(latex.contains(r"\letterheading{Letter \#}{XII}"));
+    assert!(latex.contains(r"\renewcommand{\contentsname}{Notes \& Sources.}"));
+    assert!(latex.contains(r"\textbf{100\%.}"));
+
+    let gap = Gap {
+        reason: String::from("lost"),
+        rend: None,
+    };
+    let config = FormatterConfig {
+        vocabulary: Vocabulary {
+            lacuna: Some(String::from("lacuna_")),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    assert_eq!(
+        gap.format_for_latex(&config),
+        r"[\dots]\footnote{lacuna\_.} "
+    );
+}
+
+#[test]
+fn lacunae_are_named_by_the_vocabulary() {
+    let gap = Gap {
+        reason: String::from("lost"),
+        rend: None,
+    };
+    let config = FormatterConfig {
+        footnotes: true,
+        vocabulary: Vocabulary {
+            lacuna: Some(String::from("Lücke")),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    assert_eq!(
+        gap.format_for_latex(&FormatterConfig::default()),
+        r"[\dots]\footnote{lacuna.} "
+    );
+    assert_eq!(gap.format_for_latex(&config), r"[\dots]\footnote{Lücke.} ");
+}
+
+#[test]
+fn vocabulary_keys_are_checked() {
+    let value = json!({
+        "name": "Epistulae",
+        "formatter_type": "Latex",
+        "formatter_config": {
+            "title": null,
+            "author": null,
+            "catchwords": false,
+            "ref_numbers": false,
+            "footnotes": false,
+            "language": "Latin",
+            "vocabulary": { "the_edn": "The End" }
+        },
+        "source_type": "Scaife",
+        "work_infos": [{ "title": "Epistulae", "alt_title": null, "identifier": "urn:cts:latinLit:phi1318.phi001" }]
+    });
+
+    let diagnostics = validate(&value);
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].path, "$.formatter_config.vocabulary.the_edn");
+    assert_eq!(
+        diagnostics[0].message,
+        "unknown key `the_edn`, did you mean `the_end`?"
+    );
+}